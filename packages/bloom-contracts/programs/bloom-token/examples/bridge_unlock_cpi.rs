@@ -0,0 +1,81 @@
+//! Reference flow for minting BLOOM from another program through `bloom_token::mint_bloom`.
+//!
+//! A bridge (or any third-party program) that has been granted the BLOOM mint authority
+//! signs with its own PDA and lets bloom-token run the peg guard and supply accounting,
+//! instead of calling `token::mint_to` directly. Errors raised by bloom-token (for example
+//! `MintWouldBreakPeg`) surface unchanged through the CPI boundary.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use bloom_token::cpi::accounts::MintBloom;
+use bloom_token::program::BloomToken;
+use bloom_token::MintData;
+
+declare_id!("BridgeUnlockCpiExamp1e111111111111111111111");
+
+#[program]
+pub mod bridge_unlock_cpi {
+    use super::*;
+
+    /// Mint `amount` BLOOM to `to` on behalf of this program's authority PDA
+    pub fn unlock_via_cpi(ctx: Context<UnlockViaCpi>, amount: u64) -> Result<()> {
+        let seeds = &[
+            b"bridge_data".as_ref(),
+            &[*ctx.bumps.get("bridge_authority").unwrap()],
+        ];
+        let signer = &[&seeds[..]];
+
+        bloom_token::cpi::mint_bloom(
+            CpiContext::new_with_signer(
+                ctx.accounts.bloom_token_program.to_account_info(),
+                MintBloom {
+                    mint_data: ctx.accounts.mint_data.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    mint_authority: ctx.accounts.bridge_authority.to_account_info(),
+                    mint_guard: ctx.accounts.mint_guard.to_account_info(),
+                    reserve_feed: ctx.accounts.reserve_feed.to_account_info(),
+                    recipient_allowlist: ctx.accounts.recipient_allowlist.to_account_info(),
+                    recipient_whitelist: ctx.accounts.recipient_whitelist.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            "bridge_unlock".to_string(),
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct UnlockViaCpi<'info> {
+    /// CHECK: PDA holding the BLOOM mint authority; only used as a CPI signer
+    #[account(seeds = [b"bridge_data"], bump)]
+    pub bridge_authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub mint_data: Account<'info, MintData>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// CHECK: Forwarded to bloom-token, which validates it against MintData
+    pub mint_guard: AccountInfo<'info>,
+
+    /// CHECK: Forwarded to bloom-token, which validates it against MintData
+    pub reserve_feed: AccountInfo<'info>,
+
+    /// CHECK: Forwarded to bloom-token, which checks it during bootstrap
+    pub recipient_allowlist: AccountInfo<'info>,
+
+    /// CHECK: Forwarded to bloom-token, which checks it while the whitelist is enabled
+    pub recipient_whitelist: AccountInfo<'info>,
+
+    pub bloom_token_program: Program<'info, BloomToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+fn main() {}
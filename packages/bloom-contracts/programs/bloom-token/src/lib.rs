@@ -1,8 +1,25 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction as CpiInstruction};
+use anchor_lang::solana_program::program::{get_return_data, invoke};
+use anchor_lang::system_program;
 use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, Burn};
 
+pub mod math;
+
 declare_id!("BloomToken1111111111111111111111111111111111111");
 
+/// `require!` that also emits `ValidationFailed` with the offending value and the limit it broke
+macro_rules! require_reported {
+    ($cond:expr, $code:expr, $value:expr, $limit:expr) => {
+        if !($cond) {
+            return Err(validation_failed($code, $value as u128, $limit as u128));
+        }
+    };
+}
+
 #[program]
 pub mod bloom_token {
     use super::*;
@@ -14,22 +31,15 @@ pub mod bloom_token {
         symbol: String,
         decimals: u8,
     ) -> Result<()> {
-        let mint = &mut ctx.accounts.mint;
+        require!(
+            name.len() <= MAX_NAME_LEN && symbol.len() <= MAX_SYMBOL_LEN,
+            ErrorCode::MetadataTooLong
+        );
+        require!(decimals <= MAX_DECIMALS, ErrorCode::InvalidAmount);
+
+        // The `init` constraint already created the mint with `decimals` and this authority
+        let mint = &ctx.accounts.mint;
         let mint_authority = &ctx.accounts.mint_authority;
-        
-        // Initialize mint with authority
-        token::initialize_mint(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::InitializeMint {
-                    mint: mint.to_account_info(),
-                    rent: ctx.accounts.rent.to_account_info(),
-                },
-            ),
-            decimals,
-            &mint_authority.key(),
-            Some(&mint_authority.key()),
-        )?;
 
         // Store metadata
         let mint_data = &mut ctx.accounts.mint_data;
@@ -42,8 +52,35 @@ pub mod bloom_token {
         mint_data.mint_authority = mint_authority.key();
         mint_data.mint_guard = Pubkey::default();
         mint_data.reserve_feed = Pubkey::default();
+        mint_data.last_known_reserve_sats = 0;
+        mint_data.feed_rotation_grace = 0;
+        mint_data.feed_rotation_deadline = 0;
+        mint_data.btc_per_bloom = BTC_PER_BLOOM;
+        mint_data.compliance_authority = mint_authority.key();
+        mint_data.bootstrap_until = 0;
+        mint_data.views_public = true;
+        mint_data.whitelist_enabled = false;
+        mint_data.max_feed_age = 0;
+        mint_data.minting_frozen = false;
+        mint_data.memo_threshold = 0;
+        mint_data.feed_denomination = FeedDenomination::Sats;
+        mint_data.reserve_tolerance_bps = 0;
+        mint_data.protected_destinations = Vec::new();
+        mint_data.mint = mint.key();
+        mint_data.version = MINT_DATA_VERSION;
         mint_data.bump = *ctx.bumps.get("mint_data").unwrap();
 
+        emit!(MintInitialized {
+            version: MINT_INITIALIZED_VERSION,
+            mint: mint.key(),
+            mint_data: mint_data.key(),
+            mint_authority: mint_data.mint_authority,
+            name: mint_data.name.clone(),
+            symbol: mint_data.symbol.clone(),
+            decimals,
+            btc_per_bloom: mint_data.btc_per_bloom,
+        });
+
         msg!("BLOOM token mint initialized: {}", mint.key());
         Ok(())
     }
@@ -54,6 +91,7 @@ pub mod bloom_token {
         mint_data.mint_guard = mint_guard;
         
         emit!(MintGuardUpdated {
+            version: MINT_GUARD_UPDATED_VERSION,
             old_guard: Pubkey::default(),
             new_guard: mint_guard,
         });
@@ -62,18 +100,273 @@ pub mod bloom_token {
     }
 
     /// Set reserve feed program
+    ///
+    /// Starts a grace window during which `mint_bloom` falls back to the last cached
+    /// reserve figure while the new feed has not produced its first reading.
     pub fn set_reserve_feed(ctx: Context<SetReserveFeed>, reserve_feed: Pubkey) -> Result<()> {
         let mint_data = &mut ctx.accounts.mint_data;
+        let old_feed = mint_data.reserve_feed;
+        let grace_deadline = Clock::get()?
+            .unix_timestamp
+            .checked_add(mint_data.feed_rotation_grace)
+            .ok_or(ErrorCode::MathOverflow)?;
+
         mint_data.reserve_feed = reserve_feed;
+        mint_data.feed_rotation_deadline = grace_deadline;
         
-        emit!(ReserveFeedUpdated {
-            old_feed: Pubkey::default(),
+        emit!(ReserveFeedRotated {
+            version: RESERVE_FEED_ROTATED_VERSION,
+            old_feed,
             new_feed: reserve_feed,
+            grace_deadline,
         });
         
         Ok(())
     }
 
+    /// Set how long a freshly rotated reserve feed may stay empty
+    ///
+    /// At most `MAX_FEED_ROTATION_GRACE`.
+    pub fn set_feed_rotation_grace(ctx: Context<UpdateMintConfig>, feed_rotation_grace: i64) -> Result<()> {
+        require!(
+            (0..=MAX_FEED_ROTATION_GRACE).contains(&feed_rotation_grace),
+            ErrorCode::InvalidFeedRotationGrace
+        );
+
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.feed_rotation_grace = feed_rotation_grace;
+
+        Ok(())
+    }
+
+    /// Set how old a reserve feed reading may get before `mint_bloom` rejects it and
+    /// `check_feed_liveness` freezes minting
+    ///
+    /// 0 disables the staleness check; otherwise at most `MAX_FEED_AGE_LIMIT`.
+    pub fn set_max_feed_age(ctx: Context<UpdateMintConfig>, max_feed_age: i64) -> Result<()> {
+        require!(
+            (0..=MAX_FEED_AGE_LIMIT).contains(&max_feed_age),
+            ErrorCode::InvalidMaxFeedAge
+        );
+
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.max_feed_age = max_feed_age;
+
+        Ok(())
+    }
+
+    /// Let mints through when reserves fall short of the requirement by at most
+    /// `reserve_tolerance_bps`, absorbing feed rounding; 0 requires full cover
+    pub fn set_reserve_tolerance_bps(ctx: Context<UpdateMintConfig>, reserve_tolerance_bps: u16) -> Result<()> {
+        require!(
+            reserve_tolerance_bps <= MAX_RESERVE_TOLERANCE_BPS,
+            ErrorCode::InvalidReserveTolerance
+        );
+
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.reserve_tolerance_bps = reserve_tolerance_bps;
+
+        Ok(())
+    }
+
+    /// Protect or unprotect a token account from receiving mints, e.g. the bridge escrow
+    /// or the mint authority's own account
+    pub fn set_protected_destination(
+        ctx: Context<UpdateMintConfig>,
+        destination: Pubkey,
+        protected: bool,
+    ) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        let existing = mint_data.protected_destinations.iter().position(|key| *key == destination);
+        match (existing, protected) {
+            (Some(index), false) => {
+                mint_data.protected_destinations.remove(index);
+            }
+            (None, true) => {
+                require!(
+                    mint_data.protected_destinations.len() < MAX_PROTECTED_DESTINATIONS,
+                    ErrorCode::ProtectedDestinationListFull
+                );
+                mint_data.protected_destinations.push(destination);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Set whether the reserve feed reports sats or a USD value with a BTC/USD rate
+    pub fn set_feed_denomination(
+        ctx: Context<UpdateMintConfig>,
+        feed_denomination: FeedDenomination,
+    ) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.feed_denomination = feed_denomination;
+
+        Ok(())
+    }
+
+    /// Freeze or unfreeze minting; lifts a freeze set by `check_feed_liveness`
+    pub fn set_minting_frozen(ctx: Context<UpdateMintConfig>, frozen: bool) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.minting_frozen = frozen;
+
+        Ok(())
+    }
+
+    /// Permissionless keeper: freeze minting if the reserve feed has not updated
+    /// within `max_feed_age`
+    ///
+    /// A feed with no reading counts as stale once its rotation grace has expired.
+    /// Does nothing while the check is disabled, no feed is configured or minting is
+    /// already frozen.
+    pub fn check_feed_liveness(ctx: Context<CheckFeedLiveness>) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        if mint_data.max_feed_age == 0
+            || mint_data.reserve_feed == Pubkey::default()
+            || mint_data.minting_frozen
+        {
+            return Ok(());
+        }
+
+        require_keys_eq!(
+            ctx.accounts.reserve_feed.key(),
+            mint_data.reserve_feed,
+            ErrorCode::InvalidReserveFeed
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let (last_update, stale) = match read_reserve_feed(&ctx.accounts.reserve_feed)? {
+            Some(reading) => (reading.last_update, reading_is_stale(mint_data, &reading, now)),
+            None => (0, now > mint_data.feed_rotation_deadline),
+        };
+        if !stale {
+            return Ok(());
+        }
+
+        mint_data.minting_frozen = true;
+
+        emit!(FeedWentStale {
+            version: FEED_WENT_STALE_VERSION,
+            reserve_feed: mint_data.reserve_feed,
+            last_update,
+            max_feed_age: mint_data.max_feed_age,
+            detected_at: now,
+        });
+
+        Ok(())
+    }
+
+    /// Set the peg ratio (BLOOM per BTC)
+    pub fn set_peg(ctx: Context<UpdateMintConfig>, btc_per_bloom: u64) -> Result<()> {
+        require!(btc_per_bloom != 0, ErrorCode::InvalidPeg);
+
+        let mint_data = &mut ctx.accounts.mint_data;
+        let old_btc_per_bloom = mint_data.btc_per_bloom;
+        mint_data.btc_per_bloom = btc_per_bloom;
+
+        emit!(PegUpdated {
+            version: PEG_UPDATED_VERSION,
+            old_btc_per_bloom,
+            new_btc_per_bloom: btc_per_bloom,
+        });
+
+        Ok(())
+    }
+
+    /// Set the role that manages the bootstrap allowlist
+    pub fn set_compliance_authority(ctx: Context<UpdateMintConfig>, compliance_authority: Pubkey) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.compliance_authority = compliance_authority;
+
+        Ok(())
+    }
+
+    /// Restrict mint destinations to allowlisted wallets until `bootstrap_until`
+    pub fn set_bootstrap_until(ctx: Context<UpdateMintConfig>, bootstrap_until: i64) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.bootstrap_until = bootstrap_until;
+
+        emit!(BootstrapUpdated {
+            version: BOOTSTRAP_UPDATED_VERSION,
+            bootstrap_until,
+        });
+
+        Ok(())
+    }
+
+    /// Gate view instructions behind the mint authority while `views_public` is false
+    pub fn set_views_public(ctx: Context<UpdateMintConfig>, views_public: bool) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.views_public = views_public;
+
+        Ok(())
+    }
+
+    /// Only mint to whitelisted recipients while `whitelist_enabled` is set
+    pub fn set_whitelist_enabled(ctx: Context<UpdateMintConfig>, whitelist_enabled: bool) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.whitelist_enabled = whitelist_enabled;
+
+        Ok(())
+    }
+
+    /// Whitelist a wallet as a mint recipient (compliance only)
+    pub fn add_whitelisted_recipient(ctx: Context<AddWhitelistedRecipient>, wallet: Pubkey) -> Result<()> {
+        let entry = &mut ctx.accounts.recipient_whitelist;
+        entry.wallet = wallet;
+        entry.added_at = Clock::get()?.unix_timestamp;
+        entry.bump = *ctx.bumps.get("recipient_whitelist").unwrap();
+
+        emit!(RecipientWhitelistUpdated {
+            version: RECIPIENT_WHITELIST_UPDATED_VERSION,
+            wallet,
+            whitelisted: true,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a wallet from the mint recipient whitelist (compliance only)
+    pub fn remove_whitelisted_recipient(ctx: Context<RemoveWhitelistedRecipient>, wallet: Pubkey) -> Result<()> {
+        emit!(RecipientWhitelistUpdated {
+            version: RECIPIENT_WHITELIST_UPDATED_VERSION,
+            wallet,
+            whitelisted: false,
+        });
+
+        Ok(())
+    }
+
+    /// Allowlist a wallet for the bootstrap period (compliance only)
+    ///
+    /// The same entries gate bloom-bridge locks and unlocks during its bootstrap period.
+    pub fn add_allowlist_entry(ctx: Context<AddAllowlistEntry>, wallet: Pubkey) -> Result<()> {
+        let entry = &mut ctx.accounts.allowlist_entry;
+        entry.wallet = wallet;
+        entry.added_at = Clock::get()?.unix_timestamp;
+        entry.bump = *ctx.bumps.get("allowlist_entry").unwrap();
+
+        emit!(AllowlistUpdated {
+            version: ALLOWLIST_UPDATED_VERSION,
+            wallet,
+            allowed: true,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a wallet from the bootstrap allowlist (compliance only)
+    pub fn remove_allowlist_entry(ctx: Context<RemoveAllowlistEntry>, wallet: Pubkey) -> Result<()> {
+        emit!(AllowlistUpdated {
+            version: ALLOWLIST_UPDATED_VERSION,
+            wallet,
+            allowed: false,
+        });
+
+        Ok(())
+    }
+
     /// Mint BLOOM tokens with peg enforcement
     pub fn mint_bloom(
         ctx: Context<MintBloom>,
@@ -81,66 +374,260 @@ pub mod bloom_token {
         reason: String,
     ) -> Result<()> {
         let mint_data = &mut ctx.accounts.mint_data;
-        
-        // Check if minting is allowed (peg enforcement)
-        if mint_data.mint_guard != Pubkey::default() {
-            let mint_guard_account = &ctx.accounts.mint_guard;
-            let can_mint = invoke(
-                &CpiInstruction {
-                    program_id: mint_data.mint_guard,
-                    accounts: mint_guard_account.to_account_infos(),
-                    data: vec![], // Would contain amount in real implementation
-                },
-                &mint_guard_account.to_account_infos(),
+        require!(!mint_data.minting_frozen, ErrorCode::MintingFrozen);
+
+        // During bootstrap only allowlisted wallets may receive BLOOM
+        let now = Clock::get()?.unix_timestamp;
+        if now < mint_data.bootstrap_until {
+            require_reported!(
+                is_allowlisted(&ctx.accounts.recipient_allowlist, &ctx.accounts.to.owner),
+                ErrorCode::BootstrapRestricted,
+                now,
+                mint_data.bootstrap_until
+            );
+        }
+
+        if mint_data.whitelist_enabled {
+            require_reported!(
+                is_whitelisted_recipient(&ctx.accounts.recipient_whitelist, &ctx.accounts.to.owner),
+                ErrorCode::RecipientNotWhitelisted,
+                amount,
+                0
             );
-            
-            if can_mint.is_err() {
-                return Err(ErrorCode::MintWouldBreakPeg.into());
-            }
         }
+        // A frozen account would only fail inside the mint CPI, with an opaque error
+        require_reported!(!ctx.accounts.to.is_frozen(), ErrorCode::RecipientFrozen, amount, 0);
+        require_reported!(
+            !mint_data.protected_destinations.contains(&ctx.accounts.to.key()),
+            ErrorCode::ProtectedDestination,
+            amount,
+            0
+        );
+        
+        // Check if minting is allowed (peg enforcement)
+        check_mint_guard(mint_data, &ctx.accounts.mint_guard, amount as u128)?;
 
-        // Mint tokens
-        let seeds = &[
-            b"mint_data",
-            &[mint_data.bump],
-        ];
-        let signer = &[&seeds[..]];
+        // Check reserves cover the post-mint supply
+        let reserve_sats = check_reserves(mint_data, &ctx.accounts.reserve_feed, amount as u128)?;
 
+        // Mint tokens; the SPL mint authority is the signing mint_authority, not a PDA
         token::mint_to(
-            CpiContext::new_with_signer(
+            CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
                 token::MintTo {
                     mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.to.to_account_info(),
                     authority: ctx.accounts.mint_authority.to_account_info(),
                 },
-                signer,
             ),
             amount,
         )?;
 
         // Update supply statistics
-        mint_data.total_supply += amount;
-        mint_data.total_minted += amount;
+        record_mint(mint_data, amount)?;
+        let (required_sats, dust) = peg_requirement(mint_data, amount)?;
 
         emit!(Mint {
+            version: MINT_VERSION,
             to: ctx.accounts.to.key(),
             amount,
             reason,
         });
 
         emit!(PegEnforced {
+            version: PEG_ENFORCED_VERSION,
             bloom_amount: amount,
-            required_sats: amount * SATS_PER_BLOOM,
+            required_sats,
+            dust,
+        });
+        emit_collateral_ratio(mint_data, reserve_sats)?;
+
+        Ok(())
+    }
+
+    /// Mint BLOOM to several recipients in one instruction
+    ///
+    /// Recipient token accounts are passed as remaining accounts in the same order as
+    /// `amounts`. A recipient may appear only once: duplicates are rejected with
+    /// `DuplicateRecipient` rather than merged, so each recipient gets exactly one mint
+    /// and one `Mint` event, emitted in list order. Guard and reserve checks run once
+    /// against the batch total. Unavailable during bootstrap or while the recipient
+    /// whitelist is enabled; use `mint_bloom` instead.
+    pub fn batch_mint_bloom<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchMintBloom<'info>>,
+        amounts: Vec<u64>,
+        reason: String,
+    ) -> Result<()> {
+        let recipients = ctx.remaining_accounts;
+        require!(
+            !amounts.is_empty() && amounts.len() == recipients.len(),
+            ErrorCode::RecipientCountMismatch
+        );
+
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(recipients.len());
+        for recipient in recipients.iter() {
+            require!(!seen.contains(recipient.key), ErrorCode::DuplicateRecipient);
+            seen.push(*recipient.key);
+        }
+
+        let total: u128 = amounts.iter().map(|amount| *amount as u128).sum();
+        let total_amount = u64::try_from(total).map_err(|_| ErrorCode::InvalidAmount)?;
+
+        let mint_data = &mut ctx.accounts.mint_data;
+        require!(!mint_data.minting_frozen, ErrorCode::MintingFrozen);
+        require!(
+            Clock::get()?.unix_timestamp >= mint_data.bootstrap_until,
+            ErrorCode::BootstrapRestricted
+        );
+        require!(!mint_data.whitelist_enabled, ErrorCode::RecipientNotWhitelisted);
+        check_mint_guard(mint_data, &ctx.accounts.mint_guard, total)?;
+        let reserve_sats = check_reserves(mint_data, &ctx.accounts.reserve_feed, total)?;
+
+        for (recipient, amount) in recipients.iter().zip(amounts.iter()) {
+            let to: Account<TokenAccount> = Account::try_from(recipient)?;
+            require_keys_eq!(to.mint, ctx.accounts.mint.key(), ErrorCode::InvalidRecipientAccount);
+            require!(
+                !mint_data.protected_destinations.contains(recipient.key),
+                ErrorCode::ProtectedDestination
+            );
+
+            token::mint_to(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::MintTo {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: recipient.clone(),
+                        authority: ctx.accounts.mint_authority.to_account_info(),
+                    },
+                ),
+                *amount,
+            )?;
+
+            emit!(Mint {
+                version: MINT_VERSION,
+                to: recipient.key(),
+                amount: *amount,
+                reason: reason.clone(),
+            });
+        }
+
+        record_mint(mint_data, total_amount)?;
+        let (required_sats, dust) = peg_requirement(mint_data, total_amount)?;
+
+        emit!(PegEnforced {
+            version: PEG_ENFORCED_VERSION,
+            bloom_amount: total_amount,
+            required_sats,
+            dust,
+        });
+        emit_collateral_ratio(mint_data, reserve_sats)?;
+
+        Ok(())
+    }
+
+    /// Drip test BLOOM to the caller, capped per wallet per UTC day
+    ///
+    /// Only compiled into `devnet`/`localnet` builds. Still counts toward supply so
+    /// the supply invariants hold on test clusters. The SPL mint authority is the
+    /// recorded `mint_authority` wallet, so it co-signs every drip (typically a faucet
+    /// service holding the test-cluster key).
+    #[cfg(any(feature = "devnet", feature = "localnet"))]
+    pub fn devnet_faucet(ctx: Context<DevnetFaucet>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        let today = now / SECONDS_PER_DAY;
+        let faucet_record = &mut ctx.accounts.faucet_record;
+        // init_if_needed: stamp the record on first use, never re-point it afterwards
+        if faucet_record.wallet == Pubkey::default() {
+            faucet_record.wallet = ctx.accounts.user.key();
+            faucet_record.bump = *ctx.bumps.get("faucet_record").unwrap();
+        }
+        require!(
+            faucet_record.wallet == ctx.accounts.user.key()
+                && faucet_record.bump == *ctx.bumps.get("faucet_record").unwrap(),
+            ErrorCode::ReinitializationAttempt
+        );
+        if faucet_record.day != today {
+            faucet_record.day = today;
+            faucet_record.dripped_today = 0;
+        }
+        let dripped_today = faucet_record
+            .dripped_today
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(dripped_today <= FAUCET_DAILY_CAP, ErrorCode::FaucetCapExceeded);
+
+        faucet_record.dripped_today = dripped_today;
+        faucet_record.last_drip = now;
+
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        record_mint(&mut ctx.accounts.mint_data, amount)?;
+
+        emit!(Mint {
+            version: MINT_VERSION,
+            to: ctx.accounts.to.key(),
+            amount,
+            reason: "devnet_faucet".to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Mint BLOOM signed by the mint's SPL authority itself, recording it in `MintData`
+    ///
+    /// bloom-bridge mints its unlocks through this with its `bridge_data` PDA as the
+    /// authority, so `total_supply` covers bridge-minted supply and `burn_bloom` can
+    /// later burn it. The token program rejects any signer that is not the mint's
+    /// authority. Reserve and guard checks are skipped: unlocks are backed by the
+    /// tokens locked on the EVM side.
+    pub fn bridge_mint_bloom(ctx: Context<BridgeMintBloom>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        record_mint(&mut ctx.accounts.mint_data, amount)?;
+
+        emit!(Mint {
+            version: MINT_VERSION,
+            to: ctx.accounts.to.key(),
+            amount,
+            reason: "bridge_unlock".to_string(),
         });
 
         Ok(())
     }
 
     /// Burn BLOOM tokens (used in redemption)
+    ///
+    /// `category` classifies the supply reduction for analytics; `reason` stays free-form.
+    /// When a reserve feed is configured, its program is told how many sats of
+    /// collateral the burn released so it can lower its tracked obligations.
     pub fn burn_bloom(
         ctx: Context<BurnBloom>,
         amount: u64,
+        category: BurnCategory,
         reason: String,
     ) -> Result<()> {
         let mint_data = &mut ctx.accounts.mint_data;
@@ -159,130 +646,769 @@ pub mod bloom_token {
         )?;
 
         // Update supply statistics
-        mint_data.total_supply -= amount;
-        mint_data.total_burned += amount;
+        mint_data.total_supply = mint_data
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        mint_data.total_burned = mint_data
+            .total_burned
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        notify_reserve_release(
+            mint_data,
+            &ctx.accounts.reserve_feed,
+            &ctx.accounts.reserve_feed_program,
+            amount,
+        )?;
 
         emit!(Burn {
+            version: BURN_VERSION,
             from: ctx.accounts.from.key(),
             amount,
             reason,
+            category,
+            total_supply: mint_data.total_supply,
         });
 
         Ok(())
     }
 
-    /// Get peg information
-    pub fn get_peg_info(_ctx: Context<GetPegInfo>) -> Result<PegInfo> {
-        Ok(PegInfo {
-            bloom_per_btc: BTC_PER_BLOOM,
-            sats_per_bloom: SATS_PER_BLOOM,
-            peg_statement: "Peg: 10 BLOOM = 1 BTC".to_string(),
-        })
+    /// Transfer BLOOM with a travel-rule memo hash
+    ///
+    /// Transfers above `memo_threshold` also create a `TransferMemoRecord` PDA tying
+    /// the memo hash to the transfer. Plain SPL transfers above the threshold cannot be
+    /// blocked, but compliance tooling spots them by their missing record. Bridge locks move
+    /// their own funds, so they reference a memo filed with `file_transfer_memo` instead.
+    pub fn transfer_with_memo(
+        ctx: Context<TransferWithMemo>,
+        amount: u64,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.from.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let memo_threshold = ctx.accounts.mint_data.memo_threshold;
+        if memo_threshold == 0 || amount <= memo_threshold {
+            return Ok(());
+        }
+
+        require!(memo_hash != [0u8; 32], ErrorCode::MissingTransferMemo);
+        let slot = Clock::get()?.slot;
+        create_memo_record(
+            &ctx.accounts.owner,
+            &ctx.accounts.memo_record,
+            &ctx.accounts.system_program,
+            TransferMemoRecord {
+                from: ctx.accounts.owner.key(),
+                to: ctx.accounts.to.key(),
+                amount,
+                memo_hash,
+                slot,
+                bump: 0,
+                settled: true,
+            },
+        )?;
+
+        emit!(TransferMemoRecorded {
+            version: TRANSFER_MEMO_RECORDED_VERSION,
+            from: ctx.accounts.owner.key(),
+            to: ctx.accounts.to.key(),
+            amount,
+            memo_hash,
+            slot,
+            settled: true,
+        });
+
+        Ok(())
+    }
+
+    /// File a travel-rule memo for a transfer that has not happened yet
+    ///
+    /// Records the intent to send `amount` to `to` without moving funds. A bridge lock
+    /// above `memo_threshold` references such a memo for its escrow transfer and
+    /// settles it.
+    pub fn file_transfer_memo(
+        ctx: Context<FileTransferMemo>,
+        to: Pubkey,
+        amount: u64,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(memo_hash != [0u8; 32], ErrorCode::MissingTransferMemo);
+
+        let slot = Clock::get()?.slot;
+        create_memo_record(
+            &ctx.accounts.owner,
+            &ctx.accounts.memo_record,
+            &ctx.accounts.system_program,
+            TransferMemoRecord {
+                from: ctx.accounts.owner.key(),
+                to,
+                amount,
+                memo_hash,
+                slot,
+                bump: 0,
+                settled: false,
+            },
+        )?;
+
+        emit!(TransferMemoRecorded {
+            version: TRANSFER_MEMO_RECORDED_VERSION,
+            from: ctx.accounts.owner.key(),
+            to,
+            amount,
+            memo_hash,
+            slot,
+            settled: false,
+        });
+
+        Ok(())
+    }
+
+    /// Mark a filed `TransferMemoRecord` settled so it covers only one bridge lock
+    ///
+    /// Signed by the record's `from` wallet; bloom-bridge forwards the locking user's
+    /// signature from `lock_tokens`.
+    pub fn consume_transfer_memo(ctx: Context<ConsumeTransferMemo>) -> Result<()> {
+        let memo_record = &mut ctx.accounts.memo_record;
+        require!(!memo_record.settled, ErrorCode::InvalidMemoRecord);
+        memo_record.settled = true;
+
+        Ok(())
     }
+
+    /// Set the transfer amount above which `transfer_with_memo` records its memo
+    /// and bridge locks must reference one; 0 disables memo records
+    pub fn set_memo_threshold(ctx: Context<UpdateMintConfig>, memo_threshold: u64) -> Result<()> {
+        let mint_data = &mut ctx.accounts.mint_data;
+        mint_data.memo_threshold = memo_threshold;
+
+        Ok(())
+    }
+
+    /// Hash this program's deployed ELF and record whether it matches `expected_hash`
+    /// (mint authority only)
+    ///
+    /// Hashing the whole ELF is compute-heavy; request a raised compute unit limit.
+    pub fn attest_program_hash(ctx: Context<AttestProgramHash>, expected_hash: [u8; 32]) -> Result<()> {
+        let actual_hash = deployed_program_hash(&ID, &ctx.accounts.program_data)
+            .ok_or(ErrorCode::InvalidProgramData)?;
+        let slot = Clock::get()?.slot;
+
+        let attestation = &mut ctx.accounts.build_attestation;
+        attestation.expected_hash = expected_hash;
+        attestation.actual_hash = actual_hash;
+        attestation.matched = actual_hash == expected_hash;
+        attestation.slot = slot;
+        attestation.bump = *ctx.bumps.get("build_attestation").unwrap();
+
+        emit!(ProgramHashAttested {
+            version: PROGRAM_HASH_ATTESTED_VERSION,
+            program_id: ID,
+            expected_hash,
+            actual_hash,
+            matched: attestation.matched,
+            slot,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless keeper canary: fail unless a fresh reserve feed reading covers the
+    /// whole supply at the peg
+    ///
+    /// Unlike the views, a missing, empty or stale feed fails too, and no reserve
+    /// tolerance applies, so a monitoring transaction carrying this instruction only
+    /// lands while the peg provably holds.
+    pub fn assert_peg_invariant(ctx: Context<AssertPegInvariant>) -> Result<()> {
+        let mint_data = &ctx.accounts.mint_data;
+        require!(mint_data.reserve_feed != Pubkey::default(), ErrorCode::InvalidReserveFeed);
+        require_keys_eq!(
+            ctx.accounts.reserve_feed.key(),
+            mint_data.reserve_feed,
+            ErrorCode::InvalidReserveFeed
+        );
+
+        let reading = read_reserve_feed(&ctx.accounts.reserve_feed)?.ok_or(ErrorCode::ReserveFeedEmpty)?;
+        let now = Clock::get()?.unix_timestamp;
+        require_reported!(
+            !reading_is_stale(mint_data, &reading, now),
+            ErrorCode::ReserveFeedStale,
+            now.saturating_sub(reading.last_update),
+            mint_data.max_feed_age
+        );
+
+        let reserve_sats = reading.reserve_sats(mint_data.feed_denomination)?;
+        let (required_sats, _) = math::required_sats(
+            mint_data.total_supply as u128,
+            mint_data.decimals,
+            sats_per_bloom(mint_data.btc_per_bloom)?,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+        require_reported!(
+            reserve_sats as u128 >= required_sats,
+            ErrorCode::InsufficientReserves,
+            reserve_sats,
+            required_sats
+        );
+
+        Ok(())
+    }
+
+    /// Get the reserve feed's current reading against the supply it must back
+    ///
+    /// Reports staleness under the current `max_feed_age` rather than failing, so
+    /// operators can see what `mint_bloom` would reject.
+    pub fn get_reserve_status(ctx: Context<GetReserveStatus>) -> Result<ReserveStatus> {
+        let mint_data = &ctx.accounts.mint_data;
+        check_view_access(mint_data, &ctx.accounts.viewer)?;
+        let snapshot = reserve_snapshot(mint_data, &ctx.accounts.reserve_feed, 0)?;
+
+        Ok(ReserveStatus {
+            reserve_sats: snapshot.reserve_sats,
+            required_sats: u64::try_from(snapshot.required_sats).map_err(|_| error!(ErrorCode::MathOverflow))?,
+            collateral_ratio_bps: math::collateral_ratio_bps(snapshot.reserve_sats, snapshot.required_sats),
+            last_update: snapshot.last_update,
+            max_feed_age: mint_data.max_feed_age,
+            stale: snapshot.stale,
+            zero_reserves: snapshot.zero_reserves(mint_data),
+            minting_frozen: mint_data.minting_frozen,
+        })
+    }
+
+    /// Report whether `mint_bloom` would pass its freeze and reserve checks for `amount`
+    ///
+    /// Each reserve failure maps to its own `MintOutcome`, so a feed reporting zero
+    /// reads as `ZeroReserves` rather than a generic shortfall. Guard, whitelist and
+    /// bootstrap checks are not simulated.
+    pub fn simulate_mint(ctx: Context<GetReserveStatus>, amount: u64) -> Result<MintSimulation> {
+        let mint_data = &ctx.accounts.mint_data;
+        check_view_access(mint_data, &ctx.accounts.viewer)?;
+        let snapshot = reserve_snapshot(mint_data, &ctx.accounts.reserve_feed, amount as u128)?;
+
+        let no_feed = mint_data.reserve_feed == Pubkey::default();
+        // Same order as `mint_bloom`: freeze, then feed freshness, then cover
+        let outcome = if mint_data.minting_frozen {
+            MintOutcome::MintingFrozen
+        } else if no_feed {
+            MintOutcome::Allowed
+        } else if snapshot.stale {
+            MintOutcome::FeedStale
+        } else if snapshot.feed_empty {
+            MintOutcome::FeedEmpty
+        } else if snapshot.reserve_sats as u128 >= snapshot.required_sats {
+            MintOutcome::Allowed
+        } else if snapshot.zero_reserves(mint_data) {
+            MintOutcome::ZeroReserves
+        } else if within_reserve_tolerance(mint_data, snapshot.reserve_sats, snapshot.required_sats)? {
+            MintOutcome::WithinTolerance
+        } else {
+            MintOutcome::InsufficientReserves
+        };
+
+        Ok(MintSimulation {
+            outcome,
+            reserve_sats: snapshot.reserve_sats,
+            required_sats: u64::try_from(snapshot.required_sats).map_err(|_| error!(ErrorCode::MathOverflow))?,
+            collateral_ratio_bps: math::collateral_ratio_bps(snapshot.reserve_sats, snapshot.required_sats),
+        })
+    }
+
+    /// Get peg information
+    pub fn get_peg_info(ctx: Context<GetPegInfo>) -> Result<PegInfo> {
+        check_view_access(&ctx.accounts.mint_data, &ctx.accounts.viewer)?;
+        let btc_per_bloom = ctx.accounts.mint_data.btc_per_bloom;
+        Ok(PegInfo {
+            bloom_per_btc: btc_per_bloom,
+            sats_per_bloom: sats_per_bloom(btc_per_bloom)?,
+            peg_statement: format!("Peg: {} BLOOM = 1 BTC", btc_per_bloom),
+        })
+    }
+}
+
+// Constants
+const SATS_PER_BTC: u64 = 100_000_000;
+const SATS_DECIMALS: u8 = 8;
+const BTC_PER_BLOOM: u64 = 10;
+const SATS_PER_BLOOM: u64 = SATS_PER_BTC / BTC_PER_BLOOM; // 10,000,000 sats per BLOOM
+const _: () = assert!(BTC_PER_BLOOM != 0, "default peg must be non-zero");
+/// `reason_code` a mint guard returns (first byte of its return data) to allow a mint
+pub const GUARD_ALLOW: u8 = 0;
+/// The mint would push supply past what the peg allows
+pub const GUARD_PEG_VIOLATION: u8 = 1;
+/// The mint exceeds the guard's rate limit
+pub const GUARD_RATE_LIMIT: u8 = 2;
+/// The guard has frozen minting
+pub const GUARD_FROZEN: u8 = 3;
+/// Longest `MintData.name`, in bytes
+pub const MAX_NAME_LEN: usize = 32;
+/// Longest `MintData.symbol`, in bytes
+pub const MAX_SYMBOL_LEN: usize = 10;
+/// SPL mints cap at 9 decimals, and the peg math assumes no more
+pub const MAX_DECIMALS: u8 = 9;
+/// Widest `reserve_tolerance_bps` the mint authority may set (1%)
+pub const MAX_RESERVE_TOLERANCE_BPS: u16 = 100;
+/// Longest `max_feed_age` the mint authority may set
+pub const MAX_FEED_AGE_LIMIT: i64 = 7 * 86_400;
+/// Longest `feed_rotation_grace` the mint authority may set
+pub const MAX_FEED_ROTATION_GRACE: i64 = 7 * 86_400;
+/// Most token accounts that may be protected from receiving mints at once
+pub const MAX_PROTECTED_DESTINATIONS: usize = 8;
+/// `MintData` layout written by `initialize_bloom_mint`
+pub const MINT_DATA_VERSION: u8 = 5;
+/// Oldest `MintData` layout the views will read
+pub const MIN_MINT_DATA_VERSION: u8 = 5;
+#[cfg(any(feature = "devnet", feature = "localnet"))]
+const SECONDS_PER_DAY: i64 = 86_400;
+#[cfg(any(feature = "devnet", feature = "localnet"))]
+const FAUCET_DAILY_CAP: u64 = 1_000_000_000_000; // 1,000 BLOOM at 9 decimals
+
+// Account structures
+#[derive(Accounts)]
+#[instruction(name: String, symbol: String, decimals: u8)]
+pub struct InitializeBloomMint<'info> {
+    #[account(
+        init,
+        payer = mint_authority,
+        space = 8 + MintData::LEN,
+        seeds = [b"mint_data"],
+        bump
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(
+        init,
+        payer = mint_authority,
+        mint::decimals = decimals,
+        mint::authority = mint_authority,
+        mint::freeze_authority = mint_authority,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub mint_authority: Signer<'info>,
+    
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintGuard<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    pub mint_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetReserveFeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    pub mint_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    pub mint_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CheckFeedLiveness<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    /// CHECK: Checked against `mint_data.reserve_feed` when the liveness check runs
+    pub reserve_feed: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddWhitelistedRecipient<'info> {
+    #[account(
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = compliance_authority @ ErrorCode::UnauthorizedCompliance
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(
+        init,
+        payer = compliance_authority,
+        space = 8 + WhitelistedRecipient::LEN,
+        seeds = [b"recipient_whitelist", wallet.as_ref()],
+        bump
+    )]
+    pub recipient_whitelist: Account<'info, WhitelistedRecipient>,
+    
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RemoveWhitelistedRecipient<'info> {
+    #[account(
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = compliance_authority @ ErrorCode::UnauthorizedCompliance
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(
+        mut,
+        close = compliance_authority,
+        seeds = [b"recipient_whitelist", wallet.as_ref()],
+        bump = recipient_whitelist.bump
+    )]
+    pub recipient_whitelist: Account<'info, WhitelistedRecipient>,
+    
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddAllowlistEntry<'info> {
+    #[account(
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = compliance_authority @ ErrorCode::UnauthorizedCompliance
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(
+        init,
+        payer = compliance_authority,
+        space = 8 + AllowlistEntry::LEN,
+        seeds = [b"allowlist", wallet.as_ref()],
+        bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+    
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RemoveAllowlistEntry<'info> {
+    #[account(
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = compliance_authority @ ErrorCode::UnauthorizedCompliance
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(
+        mut,
+        close = compliance_authority,
+        seeds = [b"allowlist", wallet.as_ref()],
+        bump = allowlist_entry.bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+    
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintBloom<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    
+    /// SPL mint authority set at initialization; must sign every mint
+    pub mint_authority: Signer<'info>,
+    
+    /// CHECK: This is the mint guard program
+    pub mint_guard: AccountInfo<'info>,
+    
+    /// CHECK: Validated against mint_data.reserve_feed when a feed is configured
+    pub reserve_feed: AccountInfo<'info>,
+    
+    /// CHECK: Allowlist PDA of `to.owner`; only inspected during bootstrap
+    pub recipient_allowlist: AccountInfo<'info>,
+    
+    /// CHECK: Recipient whitelist PDA of `to.owner`; only inspected while the whitelist is enabled
+    pub recipient_whitelist: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BatchMintBloom<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    /// SPL mint authority set at initialization; must sign every mint
+    pub mint_authority: Signer<'info>,
+    
+    /// CHECK: This is the mint guard program
+    pub mint_guard: AccountInfo<'info>,
+    
+    /// CHECK: Validated against mint_data.reserve_feed when a feed is configured
+    pub reserve_feed: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[cfg(any(feature = "devnet", feature = "localnet"))]
+#[derive(Accounts)]
+pub struct DevnetFaucet<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(mut, mint::authority = mint_authority)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user
+    )]
+    pub to: Account<'info, TokenAccount>,
+    
+    /// SPL mint authority set at initialization; co-signs every drip
+    pub mint_authority: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FaucetRecord::LEN,
+        seeds = [b"faucet", user.key().as_ref()],
+        bump
+    )]
+    pub faucet_record: Account<'info, FaucetRecord>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BridgeMintBloom<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    #[account(mut, address = mint_data.mint @ ErrorCode::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut, token::mint = mint)]
+    pub to: Account<'info, TokenAccount>,
+    
+    /// SPL mint authority of `mint`; the token program enforces it
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BurnBloom<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_data"],
+        bump = mint_data.bump,
+    )]
+    pub mint_data: Account<'info, MintData>,
+    
+    /// Only burns of the tracked mint may lower `total_supply` and release reserves
+    #[account(mut, address = mint_data.mint @ ErrorCode::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Validated against mint_data.reserve_feed when a feed is configured; must be writable then
+    pub reserve_feed: AccountInfo<'info>,
+    
+    /// CHECK: Must own reserve_feed when a feed is configured
+    pub reserve_feed_program: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
-// Constants
-const SATS_PER_BTC: u64 = 100_000_000;
-const BTC_PER_BLOOM: u64 = 10;
-const SATS_PER_BLOOM: u64 = SATS_PER_BTC / BTC_PER_BLOOM; // 10,000,000 sats per BLOOM
-
-// Account structures
 #[derive(Accounts)]
-pub struct InitializeBloomMint<'info> {
+pub struct TransferWithMemo<'info> {
     #[account(
-        init,
-        payer = mint_authority,
-        space = 8 + 32 + 32 + 1 + 8 + 8 + 8 + 32 + 32 + 1,
         seeds = [b"mint_data"],
-        bump
+        bump = mint_data.bump,
     )]
     pub mint_data: Account<'info, MintData>,
     
-    #[account(
-        init,
-        payer = mint_authority,
-        mint::decimals = 9,
-        mint::authority = mint_authority,
-        mint::freeze_authority = mint_authority,
-    )]
+    /// BLOOM mint, identified by the mint authority recorded at initialization
+    #[account(mint::authority = mint_data.mint_authority)]
     pub mint: Account<'info, Mint>,
     
+    #[account(mut, token::mint = mint)]
+    pub from: Account<'info, TokenAccount>,
+    
+    #[account(mut, token::mint = mint)]
+    pub to: Account<'info, TokenAccount>,
+    
+    /// CHECK: Uncreated `TransferMemoRecord` PDA of (`owner`, memo hash); only used above `memo_threshold`
     #[account(mut)]
-    pub mint_authority: Signer<'info>,
+    pub memo_record: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
     
-    pub rent: Sysvar<'info, Rent>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetMintGuard<'info> {
+pub struct FileTransferMemo<'info> {
     #[account(
-        mut,
         seeds = [b"mint_data"],
         bump = mint_data.bump,
-        has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
     )]
     pub mint_data: Account<'info, MintData>,
     
-    pub mint_authority: Signer<'info>,
+    /// CHECK: Uncreated `TransferMemoRecord` PDA of (`owner`, memo hash)
+    #[account(mut)]
+    pub memo_record: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetReserveFeed<'info> {
+pub struct ConsumeTransferMemo<'info> {
     #[account(
         mut,
+        seeds = [b"transfer_memo", owner.key().as_ref(), memo_record.memo_hash.as_ref()],
+        bump = memo_record.bump,
+    )]
+    pub memo_record: Account<'info, TransferMemoRecord>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AttestProgramHash<'info> {
+    #[account(
         seeds = [b"mint_data"],
         bump = mint_data.bump,
         has_one = mint_authority @ ErrorCode::UnauthorizedMintAuthority
     )]
     pub mint_data: Account<'info, MintData>,
     
+    #[account(
+        init_if_needed,
+        payer = mint_authority,
+        space = 8 + BuildAttestation::LEN,
+        seeds = [b"build_attestation"],
+        bump
+    )]
+    pub build_attestation: Account<'info, BuildAttestation>,
+    
+    /// CHECK: Checked to be this program's upgradeable-loader program data account
+    pub program_data: AccountInfo<'info>,
+    
+    #[account(mut)]
     pub mint_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct MintBloom<'info> {
+pub struct AssertPegInvariant<'info> {
     #[account(
-        mut,
         seeds = [b"mint_data"],
         bump = mint_data.bump,
     )]
     pub mint_data: Account<'info, MintData>,
     
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    
-    /// CHECK: This is the mint authority
-    pub mint_authority: AccountInfo<'info>,
-    
-    /// CHECK: This is the mint guard program
-    pub mint_guard: AccountInfo<'info>,
-    
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Must be `mint_data.reserve_feed`
+    pub reserve_feed: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct BurnBloom<'info> {
+pub struct GetReserveStatus<'info> {
     #[account(
-        mut,
         seeds = [b"mint_data"],
         bump = mint_data.bump,
     )]
     pub mint_data: Account<'info, MintData>,
     
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
-    
-    pub authority: Signer<'info>,
+    /// CHECK: Checked against `mint_data.reserve_feed` when a feed is configured
+    pub reserve_feed: AccountInfo<'info>,
     
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Must be the mint authority as a signer while views are not public
+    pub viewer: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -292,6 +1418,9 @@ pub struct GetPegInfo<'info> {
         bump = mint_data.bump,
     )]
     pub mint_data: Account<'info, MintData>,
+    
+    /// CHECK: Must be the mint authority as a signer while views are not public
+    pub viewer: AccountInfo<'info>,
 }
 
 // Data structures
@@ -306,9 +1435,222 @@ pub struct MintData {
     pub mint_authority: Pubkey,
     pub mint_guard: Pubkey,
     pub reserve_feed: Pubkey,
+    pub last_known_reserve_sats: u64,
+    pub feed_rotation_grace: i64,
+    pub feed_rotation_deadline: i64,
+    pub btc_per_bloom: u64,
+    pub compliance_authority: Pubkey,
+    pub bootstrap_until: i64,
+    pub views_public: bool,
+    pub whitelist_enabled: bool,
+    /// Longest a reserve feed reading may go without updating; 0 disables the liveness check
+    pub max_feed_age: i64,
+    /// Set by `check_feed_liveness` when the feed goes stale; blocks `mint_bloom`
+    pub minting_frozen: bool,
+    /// Transfers above this carry a `TransferMemoRecord`; 0 disables memo records
+    pub memo_threshold: u64,
+    /// Unit the reserve feed reports in
+    pub feed_denomination: FeedDenomination,
+    /// How far below the requirement reserves may read and still allow a mint
+    pub reserve_tolerance_bps: u16,
+    /// Token accounts `mint_bloom` and `batch_mint_bloom` refuse to mint to
+    pub protected_destinations: Vec<Pubkey>,
+    /// SPL mint this account tracks; `bridge_mint_bloom` only records mints of it
+    pub mint: Pubkey,
+    /// Layout this account was written under; zero on accounts grown by a realloc
+    pub version: u8,
+    pub bump: u8,
+}
+
+impl MintData {
+    /// Serialized size without the discriminator, in field order; strings are
+    /// length-prefixed and capped at `MAX_NAME_LEN` / `MAX_SYMBOL_LEN` bytes
+    pub const LEN: usize = 4 + MAX_NAME_LEN + 4 + MAX_SYMBOL_LEN // name, symbol
+        + 1 + 8 + 8 + 8 // decimals, total_supply, total_minted, total_burned
+        + 32 + 32 + 32 // mint_authority, mint_guard, reserve_feed
+        + 8 + 8 + 8 + 8 // last_known_reserve_sats, feed_rotation_grace, _deadline, btc_per_bloom
+        + 32 + 8 // compliance_authority, bootstrap_until
+        + 1 + 1 // views_public, whitelist_enabled
+        + 8 + 1 + 8 // max_feed_age, minting_frozen, memo_threshold
+        + 1 + 2 // feed_denomination, reserve_tolerance_bps
+        + 4 + 32 * MAX_PROTECTED_DESTINATIONS // protected_destinations
+        + 32 // mint
+        + 1 + 1; // version, bump
+}
+
+/// Marks a wallet as allowed to use BLOOM and the bridge during bootstrap
+#[account]
+pub struct AllowlistEntry {
+    pub wallet: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+impl AllowlistEntry {
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+/// Marks a wallet as allowed to receive `mint_bloom` while the whitelist is enabled
+#[account]
+pub struct WhitelistedRecipient {
+    pub wallet: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+impl WhitelistedRecipient {
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+/// Travel-rule memo hash of one above-threshold `transfer_with_memo`
+#[account]
+pub struct TransferMemoRecord {
+    /// Wallet that signed the transfer
+    pub from: Pubkey,
+    /// Destination token account
+    pub to: Pubkey,
+    pub amount: u64,
+    pub memo_hash: [u8; 32],
+    pub slot: u64,
+    pub bump: u8,
+    /// Whether funds have moved under this memo: set by `transfer_with_memo` itself,
+    /// or by the bridge lock that spends a filed memo
+    pub settled: bool,
+}
+
+impl TransferMemoRecord {
+    pub const LEN: usize = 32 + 32 + 8 + 32 + 8 + 1 + 1;
+}
+
+/// Result of the latest `attest_program_hash`
+#[account]
+pub struct BuildAttestation {
+    pub expected_hash: [u8; 32],
+    /// sha256 of the deployed ELF, trailing zero padding stripped
+    pub actual_hash: [u8; 32],
+    pub matched: bool,
+    pub slot: u64,
     pub bump: u8,
 }
 
+impl BuildAttestation {
+    pub const LEN: usize = 32 + 32 + 1 + 8 + 1;
+}
+
+/// Per-wallet devnet faucet usage for the current UTC day
+#[cfg(any(feature = "devnet", feature = "localnet"))]
+#[account]
+pub struct FaucetRecord {
+    pub wallet: Pubkey,
+    pub day: i64,
+    pub dripped_today: u64,
+    pub last_drip: i64,
+    pub bump: u8,
+}
+
+#[cfg(any(feature = "devnet", feature = "localnet"))]
+impl FaucetRecord {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 1;
+}
+
+// Accounts created through `init` must fit in a single CPI allocation
+const _: () = assert!(8 + MintData::LEN <= MAX_PERMITTED_DATA_INCREASE);
+
+/// Reading published by the reserve feed program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ReserveFeedState {
+    /// Reserves in units of 10^-feed_decimals BTC
+    pub total_sats: u64,
+    pub last_update: i64,
+    /// Decimal places of BTC the reading is denominated in (8 = sats, 11 = millisats);
+    /// 0 is read as sats so feeds written before this field keep their meaning
+    pub feed_decimals: u8,
+    /// Reserves in USD, reported by `FeedDenomination::Usd` feeds
+    pub total_usd: u64,
+    /// Price of one BTC in the same USD unit as `total_usd`
+    pub btc_usd_rate: u64,
+}
+
+impl ReserveFeedState {
+    /// The reading normalized to sats, truncated so reserves are never overstated
+    pub fn reserve_sats(&self, denomination: FeedDenomination) -> Result<u64> {
+        if denomination == FeedDenomination::Usd {
+            require!(self.btc_usd_rate > 0, ErrorCode::InvalidBtcUsdRate);
+            let sats = math::usd_to_sats(self.total_usd, self.btc_usd_rate, SATS_PER_BTC)
+                .ok_or(ErrorCode::MathOverflow)?;
+            return u64::try_from(sats).map_err(|_| error!(ErrorCode::MathOverflow));
+        }
+
+        let feed_decimals = if self.feed_decimals == 0 { SATS_DECIMALS } else { self.feed_decimals };
+        let scaled = math::scale_decimals(self.total_sats, feed_decimals, SATS_DECIMALS)
+            .ok_or(ErrorCode::MathOverflow)?;
+        u64::try_from(scaled.amount).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+}
+
+/// Unit a reserve feed reports reserves in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeedDenomination {
+    /// `total_sats`, scaled by `feed_decimals`
+    Sats,
+    /// `total_usd`, converted at the feed's `btc_usd_rate`
+    Usd,
+}
+
+/// Why supply was reduced
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BurnCategory {
+    /// BLOOM redeemed for BTC
+    Redemption,
+    /// BLOOM leaving Solana through the bridge
+    BridgeReturn,
+    /// Slashing or other enforced removal
+    Penalty,
+    /// Protocol-driven supply reduction (e.g. fee burns)
+    Deflationary,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReserveStatus {
+    /// Latest reading normalized to sats, or the last known value while the feed is empty
+    pub reserve_sats: u64,
+    /// Sats needed to back the current supply
+    pub required_sats: u64,
+    /// `reserve_sats` over `required_sats` in bps; `u64::MAX` while nothing needs backing
+    pub collateral_ratio_bps: u64,
+    /// When the feed last reported; 0 if it has no reading
+    pub last_update: i64,
+    pub max_feed_age: i64,
+    /// Whether the reading is older than `max_feed_age`
+    pub stale: bool,
+    /// Whether the configured feed reports no reserves at all
+    pub zero_reserves: bool,
+    pub minting_frozen: bool,
+}
+
+/// Result of `simulate_mint`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintSimulation {
+    pub outcome: MintOutcome,
+    pub reserve_sats: u64,
+    /// Sats needed to back the current supply plus the simulated amount
+    pub required_sats: u64,
+    pub collateral_ratio_bps: u64,
+}
+
+/// What `mint_bloom`'s freeze and reserve checks would do with a mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MintOutcome {
+    Allowed,
+    /// Allowed, with reserves short of the requirement by at most `reserve_tolerance_bps`
+    WithinTolerance,
+    MintingFrozen,
+    FeedStale,
+    FeedEmpty,
+    ZeroReserves,
+    InsufficientReserves,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct PegInfo {
     pub bloom_per_btc: u64,
@@ -316,9 +1658,41 @@ pub struct PegInfo {
     pub peg_statement: String,
 }
 
+// Event schema versions. Every event carries its version as the first field; bump the
+// matching constant whenever that event's payload changes so indexers can decode old slots.
+pub const MINT_VERSION: u8 = 1;
+pub const BURN_VERSION: u8 = 2;
+pub const PEG_ENFORCED_VERSION: u8 = 2;
+pub const MINT_GUARD_UPDATED_VERSION: u8 = 1;
+pub const RESERVE_FEED_ROTATED_VERSION: u8 = 1;
+pub const PEG_UPDATED_VERSION: u8 = 1;
+pub const BOOTSTRAP_UPDATED_VERSION: u8 = 1;
+pub const ALLOWLIST_UPDATED_VERSION: u8 = 1;
+pub const RECIPIENT_WHITELIST_UPDATED_VERSION: u8 = 1;
+pub const VALIDATION_FAILED_VERSION: u8 = 1;
+pub const FEED_WENT_STALE_VERSION: u8 = 1;
+pub const TRANSFER_MEMO_RECORDED_VERSION: u8 = 2;
+pub const PROGRAM_HASH_ATTESTED_VERSION: u8 = 1;
+pub const MINT_INITIALIZED_VERSION: u8 = 1;
+pub const RESERVE_WITHIN_TOLERANCE_VERSION: u8 = 1;
+pub const COLLATERAL_RATIO_VERSION: u8 = 1;
+
 // Events
+/// Context for the error a failed validation is about to return
+#[event]
+pub struct ValidationFailed {
+    pub version: u8,
+    /// Anchor error number (6500 + variant index)
+    pub error_code: u32,
+    /// The offending value, e.g. available reserve sats
+    pub value: u128,
+    /// The limit it was checked against, e.g. the sats the post-mint supply requires
+    pub limit: u128,
+}
+
 #[event]
 pub struct Mint {
+    pub version: u8,
     pub to: Pubkey,
     pub amount: u64,
     pub reason: String,
@@ -326,31 +1700,131 @@ pub struct Mint {
 
 #[event]
 pub struct Burn {
+    pub version: u8,
     pub from: Pubkey,
     pub amount: u64,
     pub reason: String,
+    pub category: BurnCategory,
+    /// Supply after this burn
+    pub total_supply: u64,
 }
 
 #[event]
 pub struct PegEnforced {
+    pub version: u8,
     pub bloom_amount: u64,
     pub required_sats: u64,
+    /// Fractional sats (in 10^-decimals units) rounded up into `required_sats`
+    pub dust: u64,
 }
 
 #[event]
 pub struct MintGuardUpdated {
+    pub version: u8,
     pub old_guard: Pubkey,
     pub new_guard: Pubkey,
 }
 
 #[event]
-pub struct ReserveFeedUpdated {
+pub struct PegUpdated {
+    pub version: u8,
+    pub old_btc_per_bloom: u64,
+    pub new_btc_per_bloom: u64,
+}
+
+#[event]
+pub struct BootstrapUpdated {
+    pub version: u8,
+    pub bootstrap_until: i64,
+}
+
+#[event]
+pub struct AllowlistUpdated {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct RecipientWhitelistUpdated {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub whitelisted: bool,
+}
+
+#[event]
+pub struct ReserveFeedRotated {
+    pub version: u8,
     pub old_feed: Pubkey,
     pub new_feed: Pubkey,
+    pub grace_deadline: i64,
+}
+
+/// The reserve feed missed its heartbeat and minting was frozen
+#[event]
+pub struct FeedWentStale {
+    pub version: u8,
+    pub reserve_feed: Pubkey,
+    /// Timestamp of the last reading, 0 if the feed never reported
+    pub last_update: i64,
+    pub max_feed_age: i64,
+    pub detected_at: i64,
+}
+
+#[event]
+pub struct TransferMemoRecorded {
+    pub version: u8,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub memo_hash: [u8; 32],
+    pub slot: u64,
+    /// False for a memo filed ahead of its transfer
+    pub settled: bool,
+}
+
+#[event]
+pub struct ProgramHashAttested {
+    pub version: u8,
+    pub program_id: Pubkey,
+    pub expected_hash: [u8; 32],
+    pub actual_hash: [u8; 32],
+    pub matched: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct MintInitialized {
+    pub version: u8,
+    pub mint: Pubkey,
+    pub mint_data: Pubkey,
+    pub mint_authority: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub btc_per_bloom: u64,
+}
+
+/// A mint was allowed with reserves short of the requirement but within tolerance
+#[event]
+pub struct ReserveWithinTolerance {
+    pub version: u8,
+    pub reserve_sats: u64,
+    pub required_sats: u64,
+    pub tolerance_bps: u16,
+}
+
+/// Collateralization after a mint, from the feed reading the mint was checked against
+#[event]
+pub struct CollateralRatio {
+    pub version: u8,
+    pub total_supply: u64,
+    pub reserves_sats: u64,
+    pub ratio_bps: u64,
 }
 
-// Error codes
-#[error_code]
+// Error codes: 6500-6999, so numbers never collide with bloom-bridge's 6000-6499
+#[error_code(offset = 6500)]
 pub enum ErrorCode {
     #[msg("Mint would break peg")]
     MintWouldBreakPeg,
@@ -360,4 +1834,492 @@ pub enum ErrorCode {
     InvalidAmount,
     #[msg("Insufficient balance")]
     InsufficientBalance,
+    #[msg("Reserve feed account does not match configuration")]
+    InvalidReserveFeed,
+    #[msg("Reserve feed has no reading and the rotation grace has expired")]
+    ReserveFeedEmpty,
+    #[msg("Reserves do not cover the post-mint supply")]
+    InsufficientReserves,
+    #[msg("Recipient appears more than once in the batch")]
+    DuplicateRecipient,
+    #[msg("Recipient accounts and amounts must be non-empty and equal in length")]
+    RecipientCountMismatch,
+    #[msg("Peg ratio must be non-zero")]
+    InvalidPeg,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Only allowlisted wallets may participate during bootstrap")]
+    BootstrapRestricted,
+    #[msg("Unauthorized compliance authority")]
+    UnauthorizedCompliance,
+    #[msg("Faucet daily cap reached for this wallet")]
+    FaucetCapExceeded,
+    #[msg("Account is already initialized for a different owner")]
+    ReinitializationAttempt,
+    #[msg("Recipient is not whitelisted for minting")]
+    RecipientNotWhitelisted,
+    #[msg("Token name or symbol exceeds its maximum length")]
+    MetadataTooLong,
+    #[msg("Mint guard: mint would break the peg")]
+    GuardPegViolation,
+    #[msg("Mint guard: rate limit exceeded")]
+    GuardRateLimit,
+    #[msg("Mint guard: minting is frozen")]
+    GuardFrozen,
+    #[msg("Minting is frozen until the reserve feed is live again")]
+    MintingFrozen,
+    #[msg("Transfers above the memo threshold need a memo hash")]
+    MissingTransferMemo,
+    #[msg("Memo record account does not match the transfer")]
+    InvalidMemoRecord,
+    #[msg("Recipient token account is frozen")]
+    RecipientFrozen,
+    #[msg("Account is not the program's upgradeable-loader program data")]
+    InvalidProgramData,
+    #[msg("Account was written under a layout this program no longer reads")]
+    UnsupportedAccountVersion,
+    #[msg("USD reserve feed reported a zero BTC/USD rate")]
+    InvalidBtcUsdRate,
+    #[msg("Max feed age must be between 0 and MAX_FEED_AGE_LIMIT")]
+    InvalidMaxFeedAge,
+    #[msg("Reserve feed reading is older than max_feed_age")]
+    ReserveFeedStale,
+    #[msg("Reserve tolerance exceeds MAX_RESERVE_TOLERANCE_BPS")]
+    InvalidReserveTolerance,
+    #[msg("Reserve feed reports zero reserves")]
+    ZeroReserves,
+    #[msg("Destination account is protected from receiving mints")]
+    ProtectedDestination,
+    #[msg("Too many destination accounts are protected")]
+    ProtectedDestinationListFull,
+    #[msg("Feed rotation grace must be between 0 and MAX_FEED_ROTATION_GRACE")]
+    InvalidFeedRotationGrace,
+    #[msg("Account is not the BLOOM mint MintData tracks")]
+    InvalidMint,
+    #[msg("Recipient token account does not hold the BLOOM mint")]
+    InvalidRecipientAccount,
+}
+
+// Helper functions
+
+/// Reads the feed account, returning `None` while it has not published a reading
+fn read_reserve_feed(feed: &AccountInfo) -> Result<Option<ReserveFeedState>> {
+    let data = feed.try_borrow_data()?;
+    if data.len() <= 8 {
+        return Ok(None);
+    }
+
+    let mut body = &data[8..];
+    let total_sats = u64::deserialize(&mut body)?;
+    let last_update = i64::deserialize(&mut body)?;
+    // Feeds written before `feed_decimals` existed end after `last_update`
+    let feed_decimals = if body.is_empty() { 0 } else { u8::deserialize(&mut body)? };
+    // Only USD feeds carry the trailing value and rate
+    let (total_usd, btc_usd_rate) = if body.is_empty() {
+        (0, 0)
+    } else {
+        (u64::deserialize(&mut body)?, u64::deserialize(&mut body)?)
+    };
+
+    let reading = ReserveFeedState {
+        total_sats,
+        last_update,
+        feed_decimals,
+        total_usd,
+        btc_usd_rate,
+    };
+    if reading.last_update == 0 {
+        return Ok(None);
+    }
+    Ok(Some(reading))
+}
+
+/// Whether `entry` is the live bootstrap allowlist PDA for `wallet`
+pub fn is_allowlisted(entry: &AccountInfo, wallet: &Pubkey) -> bool {
+    let (expected, _) = Pubkey::find_program_address(&[b"allowlist", wallet.as_ref()], &ID);
+    entry.key() == expected && *entry.owner == ID && !entry.data_is_empty()
+}
+
+/// Whether `entry` is the live recipient whitelist PDA for `wallet`
+fn is_whitelisted_recipient(entry: &AccountInfo, wallet: &Pubkey) -> bool {
+    let (expected, _) = Pubkey::find_program_address(&[b"recipient_whitelist", wallet.as_ref()], &ID);
+    entry.key() == expected && *entry.owner == ID && !entry.data_is_empty()
+}
+
+/// sha256 of the ELF deployed for `program_id`, or `None` if `program_data` is not
+/// its upgradeable-loader program data account
+///
+/// Trailing zero padding left by a larger `max_data_len` is stripped so the hash
+/// matches the built `.so` artifact.
+pub fn deployed_program_hash(program_id: &Pubkey, program_data: &AccountInfo) -> Option<[u8; 32]> {
+    let (expected, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
+    if program_data.key() != expected || *program_data.owner != bpf_loader_upgradeable::ID {
+        return None;
+    }
+
+    let data = program_data.try_borrow_data().ok()?;
+    let elf = data.get(UpgradeableLoaderState::size_of_programdata_metadata()..)?;
+    let end = elf.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+    Some(hash(&elf[..end]).to_bytes())
+}
+
+/// Whether `record` is an unsettled `TransferMemoRecord` of `from` to `to` covering
+/// at least `amount`
+pub fn memo_covers(record: &AccountInfo, from: &Pubkey, to: &Pubkey, amount: u64) -> bool {
+    if *record.owner != ID {
+        return false;
+    }
+    record
+        .try_borrow_data()
+        .ok()
+        .and_then(|data| TransferMemoRecord::try_deserialize(&mut &data[..]).ok())
+        .map_or(false, |memo| {
+            memo.from == *from && memo.to == *to && memo.amount >= amount && !memo.settled
+        })
+}
+
+/// Create the `TransferMemoRecord` PDA of a transfer memo
+fn create_memo_record<'info>(
+    payer: &Signer<'info>,
+    memo_record: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    mut record: TransferMemoRecord,
+) -> Result<()> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"transfer_memo", record.from.as_ref(), record.memo_hash.as_ref()],
+        &ID,
+    );
+    require_keys_eq!(memo_record.key(), expected, ErrorCode::InvalidMemoRecord);
+
+    let space = 8 + TransferMemoRecord::LEN;
+    let seeds = &[
+        b"transfer_memo".as_ref(),
+        record.from.as_ref(),
+        record.memo_hash.as_ref(),
+        &[bump],
+    ];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: payer.to_account_info(),
+                to: memo_record.clone(),
+            },
+            &[&seeds[..]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &ID,
+    )?;
+
+    record.bump = bump;
+    let mut data = memo_record.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Views are open to anyone unless `views_public` is off, then only the signing mint authority
+fn check_view_access(mint_data: &MintData, viewer: &AccountInfo) -> Result<()> {
+    // Refuse an older layout rather than misread it
+    require!(
+        mint_data.version >= MIN_MINT_DATA_VERSION,
+        ErrorCode::UnsupportedAccountVersion
+    );
+    if !mint_data.views_public {
+        require!(
+            viewer.is_signer && viewer.key() == mint_data.mint_authority,
+            ErrorCode::UnauthorizedMintAuthority
+        );
+    }
+    Ok(())
+}
+
+/// Ask the configured mint guard whether minting may proceed
+///
+/// A guard declines either by failing or by returning a `GUARD_*` reason code, which
+/// maps to its own error so clients can tell a peg breach from a rate limit or freeze.
+fn check_mint_guard(mint_data: &MintData, mint_guard: &AccountInfo, amount: u128) -> Result<()> {
+    if mint_data.mint_guard != Pubkey::default() {
+        let can_mint = invoke(
+            &CpiInstruction {
+                program_id: mint_data.mint_guard,
+                accounts: mint_guard.to_account_metas(None),
+                data: vec![], // Would contain amount in real implementation
+            },
+            &mint_guard.to_account_infos(),
+        );
+        
+        require_reported!(can_mint.is_ok(), ErrorCode::MintWouldBreakPeg, amount, 0);
+
+        // A guard that declines returns a reason_code; guards returning nothing allow
+        if let Some((program_id, data)) = get_return_data() {
+            if program_id == mint_data.mint_guard {
+                let reason_code = data.first().copied().unwrap_or(GUARD_ALLOW);
+                let code = match reason_code {
+                    GUARD_ALLOW => return Ok(()),
+                    GUARD_PEG_VIOLATION => ErrorCode::GuardPegViolation,
+                    GUARD_RATE_LIMIT => ErrorCode::GuardRateLimit,
+                    GUARD_FROZEN => ErrorCode::GuardFrozen,
+                    _ => ErrorCode::MintWouldBreakPeg,
+                };
+                return Err(validation_failed(code, amount, reason_code as u128));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tell the reserve feed program that burning `amount` released collateral
+///
+/// Sends `record_release(released_sats: u64)` to the feed's owning program. The sats
+/// are rounded down so a burn never releases more than it backed. No-op without a feed.
+fn notify_reserve_release(
+    mint_data: &MintData,
+    reserve_feed: &AccountInfo,
+    reserve_feed_program: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    if mint_data.reserve_feed == Pubkey::default() {
+        return Ok(());
+    }
+
+    require_keys_eq!(
+        reserve_feed.key(),
+        mint_data.reserve_feed,
+        ErrorCode::InvalidReserveFeed
+    );
+    require_keys_eq!(
+        reserve_feed_program.key(),
+        *reserve_feed.owner,
+        ErrorCode::InvalidReserveFeed
+    );
+
+    let released_sats = math::sats_value_floor(
+        amount,
+        mint_data.decimals,
+        sats_per_bloom(mint_data.btc_per_bloom)?,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    let released_sats = u64::try_from(released_sats).map_err(|_| error!(ErrorCode::MathOverflow))?;
+
+    let mut data = anchor_lang::solana_program::hash::hash(b"global:record_release").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&released_sats.to_le_bytes());
+
+    invoke(
+        &CpiInstruction {
+            program_id: reserve_feed_program.key(),
+            accounts: vec![AccountMeta::new(reserve_feed.key(), false)],
+            data,
+        },
+        &[reserve_feed.clone()],
+    )?;
+    Ok(())
+}
+
+/// Whether `reading` is older than `max_feed_age`; never while the check is disabled
+fn reading_is_stale(mint_data: &MintData, reading: &ReserveFeedState, now: i64) -> bool {
+    mint_data.max_feed_age > 0 && now.saturating_sub(reading.last_update) > mint_data.max_feed_age
+}
+
+/// Require a fresh reserve feed reading to cover current supply plus `amount`
+///
+/// Returns the reserve sats checked against, or `None` when no feed is configured.
+fn check_reserves(mint_data: &mut MintData, reserve_feed: &AccountInfo, amount: u128) -> Result<Option<u64>> {
+    if mint_data.reserve_feed == Pubkey::default() {
+        return Ok(None);
+    }
+
+    require_keys_eq!(
+        reserve_feed.key(),
+        mint_data.reserve_feed,
+        ErrorCode::InvalidReserveFeed
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let reserve_sats = match read_reserve_feed(reserve_feed)? {
+        Some(reading) => {
+            let age = now.saturating_sub(reading.last_update);
+            require_reported!(
+                !reading_is_stale(mint_data, &reading, now),
+                ErrorCode::ReserveFeedStale,
+                age,
+                mint_data.max_feed_age
+            );
+            let reserve_sats = reading.reserve_sats(mint_data.feed_denomination)?;
+            mint_data.last_known_reserve_sats = reserve_sats;
+            reserve_sats
+        }
+        None => {
+            // A freshly rotated feed may not have reported yet
+            require_reported!(
+                now <= mint_data.feed_rotation_deadline,
+                ErrorCode::ReserveFeedEmpty,
+                now,
+                mint_data.feed_rotation_deadline
+            );
+            mint_data.last_known_reserve_sats
+        }
+    };
+
+    let (required_sats, _) = math::required_sats(
+        mint_data.total_supply as u128 + amount,
+        mint_data.decimals,
+        sats_per_bloom(mint_data.btc_per_bloom)?,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    if reserve_sats as u128 >= required_sats {
+        return Ok(Some(reserve_sats));
+    }
+
+    // A feed reporting nothing fails closed with its own error, not as a shortfall
+    require_reported!(reserve_sats > 0, ErrorCode::ZeroReserves, reserve_sats, required_sats);
+    require_reported!(
+        within_reserve_tolerance(mint_data, reserve_sats, required_sats)?,
+        ErrorCode::InsufficientReserves,
+        reserve_sats,
+        required_sats
+    );
+
+    emit!(ReserveWithinTolerance {
+        version: RESERVE_WITHIN_TOLERANCE_VERSION,
+        reserve_sats,
+        required_sats: u64::try_from(required_sats).map_err(|_| error!(ErrorCode::MathOverflow))?,
+        tolerance_bps: mint_data.reserve_tolerance_bps,
+    });
+    Ok(Some(reserve_sats))
+}
+
+/// Whether `reserve_sats` falls short of `required_sats` by at most `reserve_tolerance_bps`
+fn within_reserve_tolerance(mint_data: &MintData, reserve_sats: u64, required_sats: u128) -> Result<bool> {
+    let floor = required_sats
+        .checked_mul((math::BPS_DENOMINATOR - mint_data.reserve_tolerance_bps as u64) as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(reserve_sats as u128 * math::BPS_DENOMINATOR as u128 >= floor)
+}
+
+/// Reserve feed reading and requirement as the views report them
+struct ReserveSnapshot {
+    /// Latest reading in sats, or `last_known_reserve_sats` without one
+    reserve_sats: u64,
+    /// Sats needed to back current supply plus the amount asked about
+    required_sats: u128,
+    last_update: i64,
+    stale: bool,
+    /// The feed has no reading and its rotation grace has expired
+    feed_empty: bool,
+}
+
+impl ReserveSnapshot {
+    fn zero_reserves(&self, mint_data: &MintData) -> bool {
+        mint_data.reserve_feed != Pubkey::default() && self.reserve_sats == 0
+    }
+}
+
+/// Read the reserve feed without enforcing anything, for the views
+fn reserve_snapshot(mint_data: &MintData, reserve_feed: &AccountInfo, amount: u128) -> Result<ReserveSnapshot> {
+    let reading = if mint_data.reserve_feed == Pubkey::default() {
+        None
+    } else {
+        require_keys_eq!(
+            reserve_feed.key(),
+            mint_data.reserve_feed,
+            ErrorCode::InvalidReserveFeed
+        );
+        read_reserve_feed(reserve_feed)?
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let (required_sats, _) = math::required_sats(
+        mint_data.total_supply as u128 + amount,
+        mint_data.decimals,
+        sats_per_bloom(mint_data.btc_per_bloom)?,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(match &reading {
+        Some(reading) => ReserveSnapshot {
+            reserve_sats: reading.reserve_sats(mint_data.feed_denomination)?,
+            required_sats,
+            last_update: reading.last_update,
+            stale: reading_is_stale(mint_data, reading, now),
+            feed_empty: false,
+        },
+        None => ReserveSnapshot {
+            reserve_sats: mint_data.last_known_reserve_sats,
+            required_sats,
+            last_update: 0,
+            stale: false,
+            feed_empty: mint_data.reserve_feed != Pubkey::default() && now > mint_data.feed_rotation_deadline,
+        },
+    })
+}
+
+/// Emit `ValidationFailed` and return `code` as an error
+///
+/// Anchor errors cannot carry data, so the offending value and the limit travel in
+/// the logs instead, where the client SDK picks them up from simulations.
+fn validation_failed(code: ErrorCode, value: u128, limit: u128) -> Error {
+    emit!(ValidationFailed {
+        version: VALIDATION_FAILED_VERSION,
+        error_code: u32::from(code),
+        value,
+        limit,
+    });
+    error!(code)
+}
+
+/// Emit `CollateralRatio` for the post-mint supply; a mint without a configured feed has no reading to report
+fn emit_collateral_ratio(mint_data: &MintData, reserve_sats: Option<u64>) -> Result<()> {
+    let reserves_sats = match reserve_sats {
+        Some(reserves_sats) => reserves_sats,
+        None => return Ok(()),
+    };
+    let (required_sats, _) = math::required_sats(
+        mint_data.total_supply as u128,
+        mint_data.decimals,
+        sats_per_bloom(mint_data.btc_per_bloom)?,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(CollateralRatio {
+        version: COLLATERAL_RATIO_VERSION,
+        total_supply: mint_data.total_supply,
+        reserves_sats,
+        ratio_bps: math::collateral_ratio_bps(reserves_sats, required_sats),
+    });
+    Ok(())
+}
+
+/// Add a mint to the supply counters
+fn record_mint(mint_data: &mut MintData, amount: u64) -> Result<()> {
+    mint_data.total_supply = mint_data
+        .total_supply
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    mint_data.total_minted = mint_data
+        .total_minted
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Sats backing `amount` at the current peg (rounded up) and the rounding dust
+fn peg_requirement(mint_data: &MintData, amount: u64) -> Result<(u64, u64)> {
+    let (sats, dust) = math::required_sats(
+        amount as u128,
+        mint_data.decimals,
+        sats_per_bloom(mint_data.btc_per_bloom)?,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    Ok((
+        u64::try_from(sats).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(dust).map_err(|_| ErrorCode::MathOverflow)?,
+    ))
+}
+
+/// Sats backing one whole BLOOM at the given peg; rejects a zero peg instead of panicking
+pub fn sats_per_bloom(btc_per_bloom: u64) -> Result<u64> {
+    SATS_PER_BTC
+        .checked_div(btc_per_bloom)
+        .ok_or_else(|| error!(ErrorCode::InvalidPeg))
 }
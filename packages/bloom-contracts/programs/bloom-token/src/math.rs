@@ -0,0 +1,92 @@
+//! Fee and peg arithmetic shared by bloom-token and bloom-bridge.
+//!
+//! Rounding policy: fees round up and net amounts round down, so truncation never
+//! favours the payer. Decimal scaling reports the sub-precision dust it drops; callers
+//! either reject it or credit it to the fee vault, and emit it in their events.
+//! Every helper returns `None` on overflow so each program can map it to its own error.
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Fee on `amount` at `fee_rate_bps`, rounded up
+pub fn fee_ceil(amount: u64, fee_rate_bps: u16) -> Option<u64> {
+    let numerator = (amount as u128).checked_mul(fee_rate_bps as u128)?;
+    let fee = (numerator + BPS_DENOMINATOR as u128 - 1) / BPS_DENOMINATOR as u128;
+    u64::try_from(fee).ok()
+}
+
+/// Split `amount` into `(fee, net)`, rounding the fee up and the net down
+pub fn split_fee(amount: u64, fee_rate_bps: u16) -> Option<(u64, u64)> {
+    let fee = fee_ceil(amount, fee_rate_bps)?;
+    let net = amount.checked_sub(fee)?;
+    Some((fee, net))
+}
+
+/// `bps` share of an already-collected `value`, rounded down
+pub fn bps_share(value: u64, bps: u16) -> Option<u64> {
+    let share = (value as u128).checked_mul(bps as u128)? / BPS_DENOMINATOR as u128;
+    u64::try_from(share).ok()
+}
+
+/// An amount moved between decimal scales
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scaled {
+    /// Amount in the target scale
+    pub amount: u128,
+    /// Source units lost below the target precision (always 0 when scaling up)
+    pub dust: u64,
+}
+
+/// Convert `amount` from `from_decimals` to `to_decimals`, truncating toward zero
+pub fn scale_decimals(amount: u64, from_decimals: u8, to_decimals: u8) -> Option<Scaled> {
+    if to_decimals >= from_decimals {
+        let factor = 10u128.checked_pow((to_decimals - from_decimals) as u32)?;
+        return Some(Scaled {
+            amount: (amount as u128).checked_mul(factor)?,
+            dust: 0,
+        });
+    }
+
+    let factor = 10u64.checked_pow((from_decimals - to_decimals) as u32)?;
+    Some(Scaled {
+        amount: (amount / factor) as u128,
+        dust: amount % factor,
+    })
+}
+
+/// Sats needed to back `amount` base units of a mint with `decimals` decimals.
+///
+/// Rounded up so reserves are never overstated. Also returns the fractional remainder
+/// that was rounded up, in units of 10^-decimals sats (0 when the amount maps to whole sats).
+pub fn required_sats(amount: u128, decimals: u8, sats_per_bloom: u64) -> Option<(u128, u128)> {
+    let scale = 10u128.checked_pow(decimals as u32)?;
+    let numerator = amount.checked_mul(sats_per_bloom as u128)?;
+    let remainder = numerator % scale;
+    let sats = numerator / scale + if remainder > 0 { 1 } else { 0 };
+    Some((sats, remainder))
+}
+
+/// Sats value of `amount` base units of a mint with `decimals` decimals, rounded down
+/// so collateral is never overstated
+pub fn sats_value_floor(amount: u64, decimals: u8, sats_per_bloom: u64) -> Option<u128> {
+    let scale = 10u128.checked_pow(decimals as u32)?;
+    Some((amount as u128).checked_mul(sats_per_bloom as u128)? / scale)
+}
+
+/// Sats worth `total_usd` at `btc_usd_rate` (both in the same USD unit), rounded down
+/// so reserves are never overstated
+pub fn usd_to_sats(total_usd: u64, btc_usd_rate: u64, sats_per_btc: u64) -> Option<u128> {
+    (total_usd as u128)
+        .checked_mul(sats_per_btc as u128)?
+        .checked_div(btc_usd_rate as u128)
+}
+
+/// `reserve_sats` as a share of `required_sats` in bps, saturating at `u64::MAX`, which
+/// also stands for "fully covered" when nothing needs backing
+pub fn collateral_ratio_bps(reserve_sats: u64, required_sats: u128) -> u64 {
+    if required_sats == 0 {
+        return u64::MAX;
+    }
+    let ratio = reserve_sats as u128 * BPS_DENOMINATOR as u128 / required_sats;
+    u64::try_from(ratio).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,38 @@
+import { describe, it, expect } from 'vitest';
+import { ComputeBudgetProgram } from '@solana/web3.js';
+import {
+  UNLOCK_BASE_COMPUTE_UNITS,
+  UNLOCK_COMPUTE_UNITS_PER_ACCOUNT,
+  estimateUnlockComputeUnits,
+  unlockComputeBudgetInstruction
+} from './compute';
+
+describe('Unlock compute estimates', () => {
+  it('should increase strictly with proof depth', () => {
+    const estimates = Array.from({ length: 32 }, (_, index) => estimateUnlockComputeUnits(index + 1));
+
+    estimates.slice(1).forEach((estimate, index) => expect(estimate).toBeGreaterThan(estimates[index]));
+    expect(estimates[0]).toBeGreaterThan(UNLOCK_BASE_COMPUTE_UNITS);
+  });
+
+  it('should add the accounts vesting and the daily cap make the unlock create', () => {
+    const plain = estimateUnlockComputeUnits(20);
+
+    expect(estimateUnlockComputeUnits(20, { vestingSeconds: 0n, unlockDailyCap: 0n })).toBe(plain);
+    expect(estimateUnlockComputeUnits(20, { vestingSeconds: 3_600n, unlockDailyCap: 1n })).toBe(
+      plain + 2 * UNLOCK_COMPUTE_UNITS_PER_ACCOUNT
+    );
+  });
+
+  it('should reject depths the program would reject', () => {
+    expect(() => estimateUnlockComputeUnits(0)).toThrow('Invalid proof depth 0');
+    expect(() => estimateUnlockComputeUnits(33)).toThrow('Invalid proof depth 33');
+  });
+
+  it('should build a matching compute budget instruction', () => {
+    const instruction = unlockComputeBudgetInstruction(16);
+
+    expect(instruction.programId.equals(ComputeBudgetProgram.programId)).toBe(true);
+    expect(instruction).toEqual(ComputeBudgetProgram.setComputeUnitLimit({ units: estimateUnlockComputeUnits(16) }));
+  });
+});
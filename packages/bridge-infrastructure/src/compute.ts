@@ -0,0 +1,46 @@
+import { ComputeBudgetProgram, type TransactionInstruction } from '@solana/web3.js';
+
+/** Mirrors bloom-bridge's `UNLOCK_BASE_COMPUTE_UNITS`; like the other `UNLOCK_*` values, unmeasured */
+export const UNLOCK_BASE_COMPUTE_UNITS = 25_000;
+/** Mirrors bloom-bridge's `UNLOCK_COMPUTE_UNITS_PER_LEVEL` */
+export const UNLOCK_COMPUTE_UNITS_PER_LEVEL = 100;
+/** Mirrors bloom-bridge's `UNLOCK_COMPUTE_UNITS_PER_ACCOUNT` */
+export const UNLOCK_COMPUTE_UNITS_PER_ACCOUNT = 5_000;
+/** Mirrors bloom-bridge's `MAX_MERKLE_DEPTH` */
+const MAX_MERKLE_DEPTH = 32;
+
+/** Bridge settings that make an unlock create extra per-user accounts */
+export interface UnlockComputeOptions {
+  vestingSeconds?: bigint;
+  unlockDailyCap?: bigint;
+}
+
+/**
+ * Rough upper bound on the compute units an `unlock_tokens` with a `proofDepth`-level
+ * proof needs, matching the program's `get_compute_estimate` view
+ *
+ * Built from unmeasured constants; confirm against a simulated transaction before
+ * relying on it as a tight limit.
+ */
+export function estimateUnlockComputeUnits(proofDepth: number, options: UnlockComputeOptions = {}): number {
+  if (!Number.isInteger(proofDepth) || proofDepth < 1 || proofDepth > MAX_MERKLE_DEPTH) {
+    throw new Error(`Invalid proof depth ${proofDepth}`);
+  }
+  const createdAccounts = [options.vestingSeconds, options.unlockDailyCap].filter(value => (value ?? 0n) > 0n).length;
+  return (
+    UNLOCK_BASE_COMPUTE_UNITS +
+    UNLOCK_COMPUTE_UNITS_PER_LEVEL * proofDepth +
+    UNLOCK_COMPUTE_UNITS_PER_ACCOUNT * createdAccounts
+  );
+}
+
+/**
+ * Compute budget instruction to prepend to an unlock transaction, requesting the rough
+ * upper bound from `estimateUnlockComputeUnits`
+ */
+export function unlockComputeBudgetInstruction(
+  proofDepth: number,
+  options: UnlockComputeOptions = {}
+): TransactionInstruction {
+  return ComputeBudgetProgram.setComputeUnitLimit({ units: estimateUnlockComputeUnits(proofDepth, options) });
+}
@@ -0,0 +1,363 @@
+import { describe, it, expect } from 'vitest';
+import { createHash } from 'crypto';
+import { PublicKey } from '@solana/web3.js';
+import {
+  AddressSetKind,
+  BRIDGE_CONFIG_SNAPSHOT_VERSION,
+  BridgeLifecycle,
+  FeedDenomination,
+  FreshnessBasis,
+  decodeAddressSet,
+  decodeBridgeData,
+  decodeMintData,
+  diffSnapshots,
+  parseSnapshot,
+  planConfigImport,
+  serializeSnapshot,
+  type BridgeConfigSnapshot,
+  type BridgeDataAccount
+} from './config';
+
+const key = (fill: number) => new PublicKey(new Uint8Array(32).fill(fill)).toBase58();
+
+const SNAPSHOT: BridgeConfigSnapshot = {
+  version: BRIDGE_CONFIG_SNAPSHOT_VERSION,
+  exportedAt: '2024-01-01T00:00:00.000Z',
+  bridgeProgramId: key(1),
+  tokenProgramId: key(2),
+  bridge: {
+    authority: key(3),
+    relayer: key(4),
+    mintGuard: key(5),
+    maxBridgeAmount: 1_000_000_000_000n,
+    minBridgeAmount: 1_000n,
+    feeRate: 30,
+    escrowCheckEnabled: true,
+    relayerFeeShareBps: 2_500,
+    paused: true,
+    haltOnViolation: true,
+    evmDecimals: 18,
+    maxScalingDust: 0n,
+    bootstrapUntil: 1_700_000_000n,
+    maxOutstandingLocks: 8,
+    viewsPublic: false,
+    minBondSats: 50_000_000n,
+    backupRootPoster: key(12),
+    backupRootDelay: 86_400n,
+    minPauseDuration: 3_600n,
+    feeBuybackEnabled: true,
+    vestingSeconds: 600n,
+    emergencyWindow: 2_592_000n,
+    strictTx: true,
+    freshnessBasis: FreshnessBasis.SLOT,
+    msPerSlot: 450n,
+    roleChangeCooldown: 86_400n,
+    minLockDuration: 3_600n,
+    chainLockCaps: [{ chainId: 42_161n, cap: 5_000_000_000n }],
+    unlockDailyCap: 2_000_000_000n,
+    minBridgeSats: 10_000n,
+    permissionlessUnlock: true,
+    feeExempt: [key(14)],
+    addressSets: {
+      [AddressSetKind.LOCK_ALLOWLIST]: Array.from({ length: 25 }, (_, i) => key(100 + i))
+    }
+  },
+  mint: {
+    name: 'Bloom',
+    symbol: 'BLOOM',
+    decimals: 9,
+    mintAuthority: key(3),
+    mintGuard: key(5),
+    reserveFeed: key(6),
+    feedRotationGrace: 3_600n,
+    btcPerBloom: 10n,
+    complianceAuthority: key(7),
+    bootstrapUntil: 1_700_000_000n,
+    viewsPublic: true,
+    whitelistEnabled: true,
+    maxFeedAge: 7_200n,
+    memoThreshold: 1_000_000_000_000n,
+    feedDenomination: FeedDenomination.USD,
+    reserveToleranceBps: 10,
+    protectedDestinations: [key(15)],
+    allowlist: [key(8), key(9)],
+    recipientWhitelist: [key(13)]
+  }
+};
+
+const {
+  feeExempt: _feeExempt,
+  chainLockCaps: _chainLockCaps,
+  addressSets: _addressSets,
+  ...SNAPSHOT_BRIDGE_SETTINGS
+} = SNAPSHOT.bridge;
+
+const FRESH_BRIDGE: BridgeDataAccount = {
+  ...SNAPSHOT_BRIDGE_SETTINGS,
+  bloomTokenMint: key(10),
+  escrowCheckEnabled: false,
+  relayerFeeShareBps: 0,
+  paused: false,
+  haltOnViolation: false,
+  bootstrapUntil: 0n,
+  maxOutstandingLocks: 0,
+  viewsPublic: true,
+  minBondSats: 0n,
+  backupRootPoster: PublicKey.default.toBase58(),
+  backupRootDelay: 0n,
+  minPauseDuration: 0n,
+  feeBuybackEnabled: false,
+  vestingSeconds: 0n,
+  emergencyWindow: 0n,
+  strictTx: false,
+  freshnessBasis: FreshnessBasis.TIMESTAMP,
+  msPerSlot: 400n,
+  roleChangeCooldown: 0n,
+  minLockDuration: 0n,
+  unlockDailyCap: 0n,
+  minBridgeSats: 0n,
+  permissionlessUnlock: false,
+  totalLocked: 0n,
+  totalUnlocked: 0n,
+  accumulatedFees: 0n,
+  currentBatchId: 0n,
+  lifecycle: BridgeLifecycle.ACTIVE,
+  successorProgram: PublicKey.default.toBase58(),
+  successorVault: PublicKey.default.toBase58(),
+  sunsetCutoff: 0n,
+  totalFeesBurned: 0n,
+  pendingAuthority: PublicKey.default.toBase58(),
+  feeExemptCount: 0,
+  processedCount: 0n,
+  lastAuthorityChange: 0n,
+  lastRelayerChange: 0n,
+  chainCaps: [],
+  lockNonce: 0n
+};
+
+function encodeBridgeData(account: BridgeDataAccount, layoutVersion = 4): Uint8Array {
+  const parts: Buffer[] = [createHash('sha256').update('account:BridgeData').digest().subarray(0, 8)];
+  const pubkey = (value: string) => parts.push(new PublicKey(value).toBuffer());
+  const int = (bytes: number, value: bigint | number) => {
+    const buf = Buffer.alloc(8);
+    buf.writeBigUInt64LE(BigInt.asUintN(64, BigInt(value)), 0);
+    parts.push(buf.subarray(0, bytes));
+  };
+  const pad = (bytes: number) => parts.push(Buffer.alloc(bytes));
+
+  pubkey(account.bloomTokenMint);
+  pubkey(account.mintGuard);
+  pubkey(account.relayer);
+  pubkey(account.authority);
+  int(8, account.maxBridgeAmount);
+  int(8, account.minBridgeAmount);
+  int(2, account.feeRate);
+  int(8, account.totalLocked);
+  pad(32 + 8);
+  int(8, account.accumulatedFees);
+  int(1, account.escrowCheckEnabled ? 1 : 0);
+  int(2, account.relayerFeeShareBps);
+  pad(8);
+  int(8, account.currentBatchId);
+  int(8, account.totalUnlocked);
+  int(1, account.paused ? 1 : 0);
+  int(1, account.haltOnViolation ? 1 : 0);
+  int(1, account.evmDecimals);
+  int(8, account.maxScalingDust);
+  int(8, account.bootstrapUntil);
+  int(2, account.maxOutstandingLocks);
+  int(1, account.viewsPublic ? 1 : 0);
+  int(8, account.minBondSats);
+  pubkey(account.backupRootPoster);
+  int(8, account.backupRootDelay);
+  pad(32 + 1 + 8);
+  int(8, account.minPauseDuration);
+  pad(8);
+  int(1, account.lifecycle);
+  pubkey(account.successorProgram);
+  pubkey(account.successorVault);
+  int(8, account.sunsetCutoff);
+  int(1, account.feeBuybackEnabled ? 1 : 0);
+  int(8, account.totalFeesBurned);
+  int(8, account.vestingSeconds);
+  pubkey(account.pendingAuthority);
+  int(8, account.emergencyWindow);
+  int(2, account.feeExemptCount);
+  int(1, account.strictTx ? 1 : 0);
+  pad(16);
+  int(1, account.freshnessBasis);
+  int(8, account.msPerSlot);
+  int(8, account.processedCount);
+  int(8, account.roleChangeCooldown);
+  int(8, account.lastAuthorityChange);
+  int(8, account.lastRelayerChange);
+  int(8, account.minLockDuration);
+  int(4, account.chainCaps.length);
+  for (const { chainId, cap, locked } of account.chainCaps) {
+    int(8, chainId);
+    int(8, cap);
+    int(8, locked);
+  }
+  int(8, account.unlockDailyCap);
+  int(8, account.minBridgeSats);
+  int(8, account.lockNonce);
+  int(1, account.permissionlessUnlock ? 1 : 0);
+  int(1, layoutVersion);
+  int(1, 255);
+  return new Uint8Array(Buffer.concat(parts));
+}
+
+function encodeMintData(
+  { allowlist: _allowlist, recipientWhitelist: _recipientWhitelist, ...mint }: BridgeConfigSnapshot['mint'],
+  layoutVersion = 5
+): Uint8Array {
+  const parts: Buffer[] = [createHash('sha256').update('account:MintData').digest().subarray(0, 8)];
+  const pubkey = (value: string) => parts.push(new PublicKey(value).toBuffer());
+  const int = (bytes: number, value: bigint | number) => {
+    const buf = Buffer.alloc(8);
+    buf.writeBigUInt64LE(BigInt.asUintN(64, BigInt(value)), 0);
+    parts.push(buf.subarray(0, bytes));
+  };
+  const pad = (bytes: number) => parts.push(Buffer.alloc(bytes));
+  const string = (value: string) => {
+    int(4, Buffer.byteLength(value));
+    parts.push(Buffer.from(value));
+  };
+
+  string(mint.name);
+  string(mint.symbol);
+  int(1, mint.decimals);
+  pad(8 * 3); // total_supply, total_minted, total_burned
+  pubkey(mint.mintAuthority);
+  pubkey(mint.mintGuard);
+  pubkey(mint.reserveFeed);
+  pad(8); // last_known_reserve_sats
+  int(8, mint.feedRotationGrace);
+  pad(8); // feed_rotation_deadline
+  int(8, mint.btcPerBloom);
+  pubkey(mint.complianceAuthority);
+  int(8, mint.bootstrapUntil);
+  int(1, mint.viewsPublic ? 1 : 0);
+  int(1, mint.whitelistEnabled ? 1 : 0);
+  int(8, mint.maxFeedAge);
+  pad(1); // minting_frozen
+  int(8, mint.memoThreshold);
+  int(1, mint.feedDenomination);
+  int(2, mint.reserveToleranceBps);
+  int(4, mint.protectedDestinations.length);
+  mint.protectedDestinations.forEach(pubkey);
+  pad(32); // mint
+  int(1, layoutVersion);
+  int(1, 254);
+  return new Uint8Array(Buffer.concat(parts));
+}
+
+describe('Config snapshots', () => {
+  it('should round-trip through JSON', () => {
+    const json = serializeSnapshot(SNAPSHOT);
+
+    expect(parseSnapshot(json)).toEqual(SNAPSHOT);
+    expect(JSON.parse(json).bridge.maxBridgeAmount).toBe('1000000000000');
+  });
+
+  it('should reject unknown snapshot versions', () => {
+    const json = serializeSnapshot({ ...SNAPSHOT, version: BRIDGE_CONFIG_SNAPSHOT_VERSION + 1 });
+
+    expect(() => parseSnapshot(json)).toThrow('Unsupported config snapshot version');
+  });
+
+  it('should decode the BridgeData account layout', () => {
+    const account = {
+      ...FRESH_BRIDGE,
+      totalLocked: 42n,
+      maxOutstandingLocks: 3,
+      minBridgeSats: 10_000n,
+      lockNonce: 12n,
+      permissionlessUnlock: true,
+      chainCaps: [{ chainId: 42_161n, cap: 5_000_000_000n, locked: 1_000n }]
+    };
+
+    expect(decodeBridgeData(encodeBridgeData(account))).toEqual(account);
+  });
+
+  it('should reject a BridgeData written under an older layout', () => {
+    // A realloc'd account from before the version byte reads as version 0
+    expect(() => decodeBridgeData(encodeBridgeData(FRESH_BRIDGE, 0))).toThrow('Unsupported BridgeData layout version 0');
+    // Version 3 predates `permissionless_unlock`
+    expect(() => decodeBridgeData(encodeBridgeData(FRESH_BRIDGE, 3))).toThrow('Unsupported BridgeData layout version 3');
+  });
+
+  it('should decode protected mint destinations from MintData', () => {
+    const { allowlist: _allowlist, recipientWhitelist: _recipientWhitelist, ...settings } = SNAPSHOT.mint;
+
+    expect(decodeMintData(encodeMintData(SNAPSHOT.mint))).toEqual(settings);
+    // Version 4 predates `mint`
+    expect(() => decodeMintData(encodeMintData(SNAPSHOT.mint, 4))).toThrow('Unsupported MintData layout version 4');
+  });
+
+  it('should decode an AddressSet account', () => {
+    const members = [key(20), key(21)];
+    const data = Buffer.concat([
+      createHash('sha256').update('account:AddressSet').digest().subarray(0, 8),
+      Buffer.from([AddressSetKind.UNLOCK_ALLOWLIST, members.length, 0, 0, 0]),
+      ...members.map(member => new PublicKey(member).toBuffer()),
+      Buffer.from([254])
+    ]);
+
+    expect(decodeAddressSet(new Uint8Array(data))).toEqual({ kind: AddressSetKind.UNLOCK_ALLOWLIST, addresses: members });
+  });
+
+  it('should report differing settings and ignore program ids', () => {
+    const redeployed: BridgeConfigSnapshot = {
+      ...SNAPSHOT,
+      bridgeProgramId: key(11),
+      bridge: { ...SNAPSHOT.bridge, feeRate: 50 }
+    };
+
+    expect(diffSnapshots(SNAPSHOT, redeployed)).toEqual([
+      { path: 'bridge.feeRate', expected: 30, actual: 50 }
+    ]);
+  });
+
+  describe('import planning', () => {
+    it('should replay every setting and pause last', () => {
+      const steps = planConfigImport(SNAPSHOT, FRESH_BRIDGE);
+
+      expect(steps).toContainEqual({ program: 'bloom-bridge', instruction: 'set_max_outstanding_locks', args: [8] });
+      expect(steps.filter(step => step.instruction === 'add_allowlist_entry')).toHaveLength(2);
+      expect(steps).toContainEqual({ program: 'bloom-bridge', instruction: 'set_min_bridge_sats', args: [10_000n] });
+      expect(steps).toContainEqual({ program: 'bloom-bridge', instruction: 'set_permissionless_unlock', args: [true] });
+      expect(steps).toContainEqual({ program: 'bloom-bridge', instruction: 'add_fee_exemption', args: [key(14)] });
+      expect(steps).toContainEqual({ program: 'bloom-token', instruction: 'set_protected_destination', args: [key(15), true] });
+      expect(steps).toContainEqual({
+        program: 'bloom-bridge',
+        instruction: 'set_chain_lock_cap',
+        args: [42_161n, 5_000_000_000n]
+      });
+      expect(steps).toContainEqual({
+        program: 'bloom-bridge',
+        instruction: 'initialize_address_set',
+        args: [AddressSetKind.LOCK_ALLOWLIST]
+      });
+      // 25 members are added in slices that fit a transaction
+      expect(
+        steps.filter(step => step.instruction === 'batch_add_addresses').map(step => (step.args[1] as string[]).length)
+      ).toEqual([20, 5]);
+      expect(steps[steps.length - 1]).toEqual({ program: 'bloom-bridge', instruction: 'set_paused', args: [true] });
+    });
+
+    it('should refuse a bridge that has started sunsetting', () => {
+      const sunsetting = { ...FRESH_BRIDGE, lifecycle: BridgeLifecycle.SUNSETTING, sunsetCutoff: 1_800_000_000n };
+
+      expect(decodeBridgeData(encodeBridgeData(sunsetting))).toEqual(sunsetting);
+      expect(() => planConfigImport(SNAPSHOT, sunsetting)).toThrow('non-default state');
+    });
+
+    it('should refuse a bridge with existing state unless forced', () => {
+      const used = { ...FRESH_BRIDGE, totalLocked: 1n };
+
+      expect(() => planConfigImport(SNAPSHOT, used)).toThrow('non-default state');
+      expect(planConfigImport(SNAPSHOT, used, { force: true }).length).toBeGreaterThan(0);
+    });
+  });
+});
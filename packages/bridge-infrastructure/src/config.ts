@@ -0,0 +1,668 @@
+import { createHash } from 'crypto';
+import { Connection, PublicKey } from '@solana/web3.js';
+import { BorshReader } from './events';
+
+/**
+ * Configuration snapshots of a bloom-bridge / bloom-token deployment.
+ *
+ * A snapshot records every admin-settable knob so a redeployment (new program ids)
+ * can be brought to the same configuration by replaying admin instructions, then
+ * diffed against the original to confirm parity. Counters and balances are not part
+ * of a snapshot.
+ */
+
+export const BRIDGE_CONFIG_SNAPSHOT_VERSION = 1;
+
+/** Mirrors bloom-bridge's `DEFAULT_EVM_DECIMALS` */
+const DEFAULT_EVM_DECIMALS = 18;
+
+/** Mirrors bloom-bridge's `DEFAULT_MS_PER_SLOT` */
+const DEFAULT_MS_PER_SLOT = 400n;
+
+/** Mirrors bloom-bridge's `MIN_BRIDGE_DATA_VERSION` */
+const MIN_BRIDGE_DATA_VERSION = 4;
+
+/** Mirrors bloom-token's `MIN_MINT_DATA_VERSION` */
+const MIN_MINT_DATA_VERSION = 5;
+
+/**
+ * Mirrors bloom-bridge's `FreshnessBasis`
+ */
+export enum FreshnessBasis {
+  TIMESTAMP = 0,
+  SLOT = 1
+}
+
+/**
+ * Mirrors bloom-bridge's `AddressSetKind`
+ */
+export enum AddressSetKind {
+  FEE_EXEMPT = 0,
+  LOCK_ALLOWLIST = 1,
+  UNLOCK_ALLOWLIST = 2
+}
+
+/**
+ * Mirrors bloom-token's `FeedDenomination`
+ */
+export enum FeedDenomination {
+  SATS = 0,
+  USD = 1
+}
+
+/** Addresses per `batch_add_addresses` step, small enough to fit one transaction */
+const ADDRESS_SET_BATCH = 20;
+
+/**
+ * Mirrors bloom-bridge's `ChainCap`, without the in-flight amount
+ */
+export interface ChainLockCap {
+  chainId: bigint;
+  cap: bigint;
+}
+
+export interface ChainCapState extends ChainLockCap {
+  /** Net locked toward the chain and not yet acknowledged or refunded */
+  locked: bigint;
+}
+
+export interface BridgeSettings {
+  authority: string;
+  relayer: string;
+  mintGuard: string;
+  maxBridgeAmount: bigint;
+  minBridgeAmount: bigint;
+  feeRate: number;
+  escrowCheckEnabled: boolean;
+  relayerFeeShareBps: number;
+  paused: boolean;
+  haltOnViolation: boolean;
+  evmDecimals: number;
+  maxScalingDust: bigint;
+  bootstrapUntil: bigint;
+  maxOutstandingLocks: number;
+  viewsPublic: boolean;
+  minBondSats: bigint;
+  backupRootPoster: string;
+  backupRootDelay: bigint;
+  minPauseDuration: bigint;
+  feeBuybackEnabled: boolean;
+  vestingSeconds: bigint;
+  emergencyWindow: bigint;
+  strictTx: boolean;
+  freshnessBasis: FreshnessBasis;
+  msPerSlot: bigint;
+  roleChangeCooldown: bigint;
+  minLockDuration: bigint;
+  chainLockCaps: ChainLockCap[];
+  unlockDailyCap: bigint;
+  minBridgeSats: bigint;
+  permissionlessUnlock: boolean;
+  /** Wallets with a `FeeExemption` entry, sorted */
+  feeExempt: string[];
+  /** Members of each initialized `AddressSet`, sorted */
+  addressSets: Partial<Record<AddressSetKind, string[]>>;
+}
+
+export interface MintSettings {
+  name: string;
+  symbol: string;
+  decimals: number;
+  mintAuthority: string;
+  mintGuard: string;
+  reserveFeed: string;
+  feedRotationGrace: bigint;
+  btcPerBloom: bigint;
+  complianceAuthority: string;
+  bootstrapUntil: bigint;
+  viewsPublic: boolean;
+  whitelistEnabled: boolean;
+  maxFeedAge: bigint;
+  memoThreshold: bigint;
+  feedDenomination: FeedDenomination;
+  reserveToleranceBps: number;
+  /** Token accounts that may not receive mints */
+  protectedDestinations: string[];
+  /** Wallets with a bootstrap `AllowlistEntry`, sorted */
+  allowlist: string[];
+  /** Wallets with a `WhitelistedRecipient` entry, sorted */
+  recipientWhitelist: string[];
+}
+
+export interface BridgeConfigSnapshot {
+  version: number;
+  exportedAt: string;
+  bridgeProgramId: string;
+  tokenProgramId: string;
+  bridge: BridgeSettings;
+  mint: MintSettings;
+}
+
+/**
+ * Mirrors bloom-bridge's `BridgeLifecycle`
+ */
+export enum BridgeLifecycle {
+  ACTIVE = 0,
+  SUNSETTING = 1,
+  MIGRATED = 2
+}
+
+/**
+ * On-chain `BridgeData`, including the counters used to tell a fresh deployment apart
+ */
+export interface BridgeDataAccount extends Omit<BridgeSettings, 'feeExempt' | 'chainLockCaps' | 'addressSets'> {
+  bloomTokenMint: string;
+  totalLocked: bigint;
+  totalUnlocked: bigint;
+  accumulatedFees: bigint;
+  currentBatchId: bigint;
+  lifecycle: BridgeLifecycle;
+  successorProgram: string;
+  successorVault: string;
+  sunsetCutoff: bigint;
+  totalFeesBurned: bigint;
+  pendingAuthority: string;
+  feeExemptCount: number;
+  processedCount: bigint;
+  lastAuthorityChange: bigint;
+  lastRelayerChange: bigint;
+  chainCaps: ChainCapState[];
+  lockNonce: bigint;
+}
+
+function accountDiscriminator(name: string): Uint8Array {
+  return new Uint8Array(createHash('sha256').update(`account:${name}`).digest().subarray(0, 8));
+}
+
+function accountBody(name: string, data: Uint8Array): BorshReader {
+  const discriminator = accountDiscriminator(name);
+  if (data.length < 8 || !discriminator.every((byte, i) => data[i] === byte)) {
+    throw new Error(`Account is not a ${name}`);
+  }
+  return new BorshReader(data.subarray(8));
+}
+
+/**
+ * Decode a bloom-bridge `BridgeData` account
+ */
+export function decodeBridgeData(data: Uint8Array): BridgeDataAccount {
+  const r = accountBody('BridgeData', data);
+  const bloomTokenMint = r.pubkey();
+  const mintGuard = r.pubkey();
+  const relayer = r.pubkey();
+  const authority = r.pubkey();
+  const maxBridgeAmount = r.u64();
+  const minBridgeAmount = r.u64();
+  const feeRate = r.u16();
+  const totalLocked = r.u64();
+  r.bytes(32); // merkle_root
+  r.i64(); // merkle_root_update_time
+  const accumulatedFees = r.u64();
+  const escrowCheckEnabled = r.bool();
+  const relayerFeeShareBps = r.u16();
+  r.u64(); // relayer_rebate_accrued
+  const currentBatchId = r.u64();
+  const totalUnlocked = r.u64();
+  const paused = r.bool();
+  const haltOnViolation = r.bool();
+  const evmDecimals = r.u8();
+  const maxScalingDust = r.u64();
+  const bootstrapUntil = r.i64();
+  const maxOutstandingLocks = r.u16();
+  const viewsPublic = r.bool();
+  const minBondSats = r.u64();
+  const backupRootPoster = r.pubkey();
+  const backupRootDelay = r.i64();
+  r.bytes(32); // backup_merkle_root
+  r.u8(); // backup_root_depth
+  r.i64(); // backup_root_posted_at
+  const minPauseDuration = r.i64();
+  r.i64(); // paused_at
+  const lifecycle = r.u8();
+  if (!(lifecycle in BridgeLifecycle)) {
+    throw new Error(`Unknown bridge lifecycle ${lifecycle}`);
+  }
+  const successorProgram = r.pubkey();
+  const successorVault = r.pubkey();
+  const sunsetCutoff = r.i64();
+  const feeBuybackEnabled = r.bool();
+  const totalFeesBurned = r.u64();
+  const vestingSeconds = r.i64();
+  const pendingAuthority = r.pubkey();
+  const emergencyWindow = r.i64();
+  const feeExemptCount = r.u16();
+  const strictTx = r.bool();
+  r.u64(); // merkle_root_update_slot
+  r.u64(); // backup_root_posted_slot
+  const freshnessBasis = r.u8();
+  if (!(freshnessBasis in FreshnessBasis)) {
+    throw new Error(`Unknown freshness basis ${freshnessBasis}`);
+  }
+  const msPerSlot = r.u64();
+  const processedCount = r.u64();
+  const roleChangeCooldown = r.i64();
+  const lastAuthorityChange = r.i64();
+  const lastRelayerChange = r.i64();
+  const minLockDuration = r.i64();
+  const chainCaps = Array.from({ length: r.u32() }, () => ({ chainId: r.u64(), cap: r.u64(), locked: r.u64() }));
+  const unlockDailyCap = r.u64();
+  const minBridgeSats = r.u64();
+  const lockNonce = r.u64();
+  const permissionlessUnlock = r.bool();
+  const layoutVersion = r.u8();
+  if (layoutVersion < MIN_BRIDGE_DATA_VERSION) {
+    throw new Error(`Unsupported BridgeData layout version ${layoutVersion}`);
+  }
+
+  return {
+    bloomTokenMint,
+    mintGuard,
+    relayer,
+    authority,
+    maxBridgeAmount,
+    minBridgeAmount,
+    feeRate,
+    totalLocked,
+    accumulatedFees,
+    escrowCheckEnabled,
+    relayerFeeShareBps,
+    currentBatchId,
+    totalUnlocked,
+    paused,
+    haltOnViolation,
+    evmDecimals,
+    maxScalingDust,
+    bootstrapUntil,
+    maxOutstandingLocks,
+    viewsPublic,
+    minBondSats,
+    backupRootPoster,
+    backupRootDelay,
+    minPauseDuration,
+    lifecycle,
+    successorProgram,
+    successorVault,
+    sunsetCutoff,
+    feeBuybackEnabled,
+    totalFeesBurned,
+    vestingSeconds,
+    pendingAuthority,
+    emergencyWindow,
+    feeExemptCount,
+    strictTx,
+    freshnessBasis,
+    msPerSlot,
+    processedCount,
+    roleChangeCooldown,
+    lastAuthorityChange,
+    lastRelayerChange,
+    minLockDuration,
+    chainCaps,
+    unlockDailyCap,
+    minBridgeSats,
+    lockNonce,
+    permissionlessUnlock
+  };
+}
+
+/**
+ * Decode a bloom-token `MintData` account (without its per-wallet lists)
+ */
+export function decodeMintData(data: Uint8Array): Omit<MintSettings, 'allowlist' | 'recipientWhitelist'> {
+  const r = accountBody('MintData', data);
+  const name = r.string();
+  const symbol = r.string();
+  const decimals = r.u8();
+  r.u64(); // total_supply
+  r.u64(); // total_minted
+  r.u64(); // total_burned
+  const mintAuthority = r.pubkey();
+  const mintGuard = r.pubkey();
+  const reserveFeed = r.pubkey();
+  r.u64(); // last_known_reserve_sats
+  const feedRotationGrace = r.i64();
+  r.i64(); // feed_rotation_deadline
+  const btcPerBloom = r.u64();
+  const complianceAuthority = r.pubkey();
+  const bootstrapUntil = r.i64();
+  const viewsPublic = r.bool();
+  const whitelistEnabled = r.bool();
+  const maxFeedAge = r.i64();
+  r.bool(); // minting_frozen
+  const memoThreshold = r.u64();
+  const feedDenomination = r.u8();
+  if (!(feedDenomination in FeedDenomination)) {
+    throw new Error(`Unknown feed denomination ${feedDenomination}`);
+  }
+  const reserveToleranceBps = r.u16();
+  const protectedDestinations = Array.from({ length: r.u32() }, () => r.pubkey());
+  r.pubkey(); // mint
+  const layoutVersion = r.u8();
+  if (layoutVersion < MIN_MINT_DATA_VERSION) {
+    throw new Error(`Unsupported MintData layout version ${layoutVersion}`);
+  }
+
+  return {
+    name,
+    symbol,
+    decimals,
+    mintAuthority,
+    mintGuard,
+    reserveFeed,
+    feedRotationGrace,
+    btcPerBloom,
+    complianceAuthority,
+    bootstrapUntil,
+    viewsPublic,
+    whitelistEnabled,
+    maxFeedAge,
+    memoThreshold,
+    feedDenomination,
+    reserveToleranceBps,
+    protectedDestinations
+  };
+}
+
+/**
+ * Decode a bloom-bridge `AddressSet` account
+ */
+export function decodeAddressSet(data: Uint8Array): { kind: AddressSetKind; addresses: string[] } {
+  const r = accountBody('AddressSet', data);
+  const kind = r.u8();
+  if (!(kind in AddressSetKind)) {
+    throw new Error(`Unknown address set kind ${kind}`);
+  }
+  const addresses = Array.from({ length: r.u32() }, () => r.pubkey());
+  return { kind, addresses };
+}
+
+function bridgeSettings(account: BridgeDataAccount): Omit<BridgeSettings, 'feeExempt' | 'addressSets'> {
+  const {
+    bloomTokenMint,
+    totalLocked,
+    totalUnlocked,
+    accumulatedFees,
+    currentBatchId,
+    lifecycle,
+    successorProgram,
+    successorVault,
+    sunsetCutoff,
+    totalFeesBurned,
+    pendingAuthority,
+    feeExemptCount,
+    processedCount,
+    lastAuthorityChange,
+    lastRelayerChange,
+    chainCaps,
+    lockNonce,
+    ...settings
+  } = account;
+  return { ...settings, chainLockCaps: chainCaps.map(({ chainId, cap }) => ({ chainId, cap })) };
+}
+
+/**
+ * Sorted wallets of every per-wallet entry account (`wallet` is their first field)
+ */
+async function fetchWalletEntries(
+  connection: Connection,
+  programId: PublicKey,
+  accountName: string
+): Promise<string[]> {
+  const accounts = await connection.getProgramAccounts(programId, {
+    filters: [
+      {
+        memcmp: {
+          offset: 0,
+          bytes: Buffer.from(accountDiscriminator(accountName)).toString('base64'),
+          encoding: 'base64'
+        }
+      }
+    ]
+  });
+  return accounts.map(({ account }) => accountBody(accountName, account.data).pubkey()).sort();
+}
+
+/**
+ * Read every config account of a deployment into a snapshot
+ */
+export async function exportConfig(
+  connection: Connection,
+  bridgeProgramId: PublicKey,
+  tokenProgramId: PublicKey
+): Promise<BridgeConfigSnapshot> {
+  const [bridgeDataAddress] = PublicKey.findProgramAddressSync([Buffer.from('bridge_data')], bridgeProgramId);
+  const [mintDataAddress] = PublicKey.findProgramAddressSync([Buffer.from('mint_data')], tokenProgramId);
+
+  const [bridgeInfo, mintInfo] = await connection.getMultipleAccountsInfo([bridgeDataAddress, mintDataAddress]);
+  if (!bridgeInfo || !mintInfo) {
+    throw new Error('Bridge or mint config account not found');
+  }
+
+  const [allowlist, recipientWhitelist, feeExempt] = await Promise.all([
+    fetchWalletEntries(connection, tokenProgramId, 'AllowlistEntry'),
+    fetchWalletEntries(connection, tokenProgramId, 'WhitelistedRecipient'),
+    fetchWalletEntries(connection, bridgeProgramId, 'FeeExemption')
+  ]);
+
+  const setAddresses = [AddressSetKind.FEE_EXEMPT, AddressSetKind.LOCK_ALLOWLIST, AddressSetKind.UNLOCK_ALLOWLIST].map(
+    kind => PublicKey.findProgramAddressSync([Buffer.from('address_set'), Buffer.from([kind])], bridgeProgramId)[0]
+  );
+  const setInfos = await connection.getMultipleAccountsInfo(setAddresses);
+  const addressSets: BridgeSettings['addressSets'] = {};
+  for (const info of setInfos) {
+    if (info) {
+      const { kind, addresses } = decodeAddressSet(info.data);
+      addressSets[kind] = addresses;
+    }
+  }
+
+  return {
+    version: BRIDGE_CONFIG_SNAPSHOT_VERSION,
+    exportedAt: new Date().toISOString(),
+    bridgeProgramId: bridgeProgramId.toBase58(),
+    tokenProgramId: tokenProgramId.toBase58(),
+    bridge: { ...bridgeSettings(decodeBridgeData(bridgeInfo.data)), feeExempt, addressSets },
+    mint: { ...decodeMintData(mintInfo.data), allowlist, recipientWhitelist }
+  };
+}
+
+function bigintReplacer(_key: string, value: unknown): unknown {
+  return typeof value === 'bigint' ? value.toString() : value;
+}
+
+/**
+ * Serialize a snapshot to JSON; bigints are written as decimal strings
+ */
+export function serializeSnapshot(snapshot: BridgeConfigSnapshot): string {
+  return JSON.stringify(snapshot, bigintReplacer, 2);
+}
+
+const BIGINT_FIELDS = new Set([
+  'maxBridgeAmount',
+  'minBridgeAmount',
+  'maxScalingDust',
+  'minBondSats',
+  'backupRootDelay',
+  'minPauseDuration',
+  'vestingSeconds',
+  'emergencyWindow',
+  'msPerSlot',
+  'roleChangeCooldown',
+  'minLockDuration',
+  'chainId',
+  'cap',
+  'unlockDailyCap',
+  'minBridgeSats',
+  'bootstrapUntil',
+  'feedRotationGrace',
+  'maxFeedAge',
+  'memoThreshold',
+  'btcPerBloom'
+]);
+
+/**
+ * Parse a snapshot written by `serializeSnapshot`
+ */
+export function parseSnapshot(json: string): BridgeConfigSnapshot {
+  const snapshot = JSON.parse(json, (key, value) =>
+    BIGINT_FIELDS.has(key) && typeof value === 'string' ? BigInt(value) : value
+  ) as BridgeConfigSnapshot;
+
+  if (snapshot.version !== BRIDGE_CONFIG_SNAPSHOT_VERSION) {
+    throw new Error(`Unsupported config snapshot version ${snapshot.version}`);
+  }
+  return snapshot;
+}
+
+export interface ConfigDifference {
+  path: string;
+  expected: unknown;
+  actual: unknown;
+}
+
+/**
+ * Settings that differ between two snapshots; program ids and export time are ignored
+ */
+export function diffSnapshots(expected: BridgeConfigSnapshot, actual: BridgeConfigSnapshot): ConfigDifference[] {
+  const differences: ConfigDifference[] = [];
+  for (const section of ['bridge', 'mint'] as const) {
+    const want = expected[section] as unknown as Record<string, unknown>;
+    const got = actual[section] as unknown as Record<string, unknown>;
+    for (const key of Object.keys(want)) {
+      if (JSON.stringify(want[key], bigintReplacer) !== JSON.stringify(got[key], bigintReplacer)) {
+        differences.push({ path: `${section}.${key}`, expected: want[key], actual: got[key] });
+      }
+    }
+  }
+  return differences;
+}
+
+/**
+ * Whether a bridge is still in the state `initialize_bridge` leaves it in
+ */
+export function isFreshBridge(account: BridgeDataAccount): boolean {
+  return (
+    account.totalLocked === 0n &&
+    account.totalUnlocked === 0n &&
+    account.accumulatedFees === 0n &&
+    account.currentBatchId === 0n &&
+    account.totalFeesBurned === 0n &&
+    account.pendingAuthority === PublicKey.default.toBase58() &&
+    account.feeExemptCount === 0 &&
+    account.processedCount === 0n &&
+    account.lastAuthorityChange === 0n &&
+    account.lastRelayerChange === 0n &&
+    account.chainCaps.length === 0 &&
+    account.lockNonce === 0n &&
+    account.lifecycle === BridgeLifecycle.ACTIVE &&
+    !account.escrowCheckEnabled &&
+    account.relayerFeeShareBps === 0 &&
+    !account.paused &&
+    !account.haltOnViolation &&
+    account.evmDecimals === DEFAULT_EVM_DECIMALS &&
+    account.maxScalingDust === 0n &&
+    account.bootstrapUntil === 0n &&
+    account.maxOutstandingLocks === 0 &&
+    account.viewsPublic &&
+    account.minBondSats === 0n &&
+    account.backupRootPoster === PublicKey.default.toBase58() &&
+    account.backupRootDelay === 0n &&
+    account.minPauseDuration === 0n &&
+    !account.feeBuybackEnabled &&
+    account.vestingSeconds === 0n &&
+    account.emergencyWindow === 0n &&
+    !account.strictTx &&
+    account.freshnessBasis === FreshnessBasis.TIMESTAMP &&
+    account.msPerSlot === DEFAULT_MS_PER_SLOT &&
+    account.roleChangeCooldown === 0n &&
+    account.minLockDuration === 0n &&
+    account.unlockDailyCap === 0n &&
+    account.minBridgeSats === 0n &&
+    !account.permissionlessUnlock
+  );
+}
+
+export interface AdminStep {
+  program: 'bloom-bridge' | 'bloom-token';
+  instruction: string;
+  args: unknown[];
+}
+
+/**
+ * Admin instructions that bring a freshly initialized deployment to `snapshot`.
+ *
+ * Limits and the fee rate are `initialize_bridge` arguments, and the mint's name,
+ * symbol and decimals are `initialize_bloom_mint` arguments; they are not replayed.
+ * Refuses to plan against a bridge with non-default state unless `force` is set.
+ */
+export function planConfigImport(
+  snapshot: BridgeConfigSnapshot,
+  current: BridgeDataAccount,
+  options: { force?: boolean } = {}
+): AdminStep[] {
+  if (!options.force && !isFreshBridge(current)) {
+    throw new Error('Target bridge already has non-default state; pass force to import anyway');
+  }
+
+  const { bridge, mint } = snapshot;
+  const bridgeStep = (instruction: string, ...args: unknown[]): AdminStep => ({
+    program: 'bloom-bridge',
+    instruction,
+    args
+  });
+  const tokenStep = (instruction: string, ...args: unknown[]): AdminStep => ({
+    program: 'bloom-token',
+    instruction,
+    args
+  });
+
+  return [
+    bridgeStep('set_relayer', bridge.relayer),
+    bridgeStep('set_escrow_check', bridge.escrowCheckEnabled),
+    bridgeStep('set_halt_on_violation', bridge.haltOnViolation),
+    bridgeStep('set_scaling_policy', bridge.evmDecimals, bridge.maxScalingDust),
+    bridgeStep('set_bootstrap_until', bridge.bootstrapUntil),
+    bridgeStep('set_max_outstanding_locks', bridge.maxOutstandingLocks),
+    bridgeStep('set_views_public', bridge.viewsPublic),
+    bridgeStep('set_relayer_fee_share', bridge.relayerFeeShareBps),
+    bridgeStep('set_min_bond_sats', bridge.minBondSats),
+    bridgeStep('set_backup_root_poster', bridge.backupRootPoster, bridge.backupRootDelay),
+    bridgeStep('set_min_pause_duration', bridge.minPauseDuration),
+    bridgeStep('set_fee_buyback', bridge.feeBuybackEnabled),
+    bridgeStep('set_vesting_seconds', bridge.vestingSeconds),
+    bridgeStep('set_emergency_window', bridge.emergencyWindow),
+    bridgeStep('set_strict_tx', bridge.strictTx),
+    bridgeStep('set_freshness_basis', bridge.freshnessBasis, bridge.msPerSlot),
+    bridgeStep('set_role_change_cooldown', bridge.roleChangeCooldown),
+    bridgeStep('set_min_lock_duration', bridge.minLockDuration),
+    bridgeStep('set_unlock_daily_cap', bridge.unlockDailyCap),
+    bridgeStep('set_min_bridge_sats', bridge.minBridgeSats),
+    bridgeStep('set_permissionless_unlock', bridge.permissionlessUnlock),
+    ...bridge.chainLockCaps.map(({ chainId, cap }) => bridgeStep('set_chain_lock_cap', chainId, cap)),
+    ...bridge.feeExempt.map(wallet => bridgeStep('add_fee_exemption', wallet)),
+    ...Object.entries(bridge.addressSets).flatMap(([kind, addresses = []]) => {
+      const steps = [bridgeStep('initialize_address_set', Number(kind))];
+      for (let i = 0; i < addresses.length; i += ADDRESS_SET_BATCH) {
+        steps.push(bridgeStep('batch_add_addresses', Number(kind), addresses.slice(i, i + ADDRESS_SET_BATCH)));
+      }
+      return steps;
+    }),
+    tokenStep('set_mint_guard', mint.mintGuard),
+    tokenStep('set_reserve_feed', mint.reserveFeed),
+    tokenStep('set_feed_rotation_grace', mint.feedRotationGrace),
+    tokenStep('set_max_feed_age', mint.maxFeedAge),
+    tokenStep('set_memo_threshold', mint.memoThreshold),
+    tokenStep('set_feed_denomination', mint.feedDenomination),
+    tokenStep('set_reserve_tolerance_bps', mint.reserveToleranceBps),
+    ...mint.protectedDestinations.map(destination => tokenStep('set_protected_destination', destination, true)),
+    tokenStep('set_peg', mint.btcPerBloom),
+    tokenStep('set_compliance_authority', mint.complianceAuthority),
+    tokenStep('set_bootstrap_until', mint.bootstrapUntil),
+    tokenStep('set_views_public', mint.viewsPublic),
+    ...mint.allowlist.map(wallet => tokenStep('add_allowlist_entry', wallet)),
+    ...mint.recipientWhitelist.map(wallet => tokenStep('add_whitelisted_recipient', wallet)),
+    tokenStep('set_whitelist_enabled', mint.whitelistEnabled),
+    // Pause last so the replay itself is not blocked
+    bridgeStep('set_paused', bridge.paused)
+  ];
+}
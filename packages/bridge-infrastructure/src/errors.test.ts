@@ -0,0 +1,80 @@
+import { describe, it, expect, vi } from 'vitest';
+import type { Connection, VersionedTransaction } from '@solana/web3.js';
+import {
+  BridgeValidationError,
+  ERROR_CODE_RANGES,
+  ERROR_NAMES,
+  resolveErrorCode,
+  simulateOrThrow,
+  validationErrorFromLogs,
+  type ProgramName
+} from './errors';
+
+// ValidationFailed { error_code: 6001 (AmountAboveMaximum), value: 2_000_000, limit: 1_000_000 }
+const AMOUNT_ABOVE_MAXIMUM = '2twZI+brhSABcRcAAICEHgAAAAAAAAAAAAAAAABAQg8AAAAAAAAAAAAAAAAA';
+
+const FAILED_LOCK_LOGS = [
+  'Program BloomBridge1111111111111111111111111111111111111 invoke [1]',
+  'Program log: Instruction: LockTokens',
+  `Program data: ${AMOUNT_ABOVE_MAXIMUM}`,
+  'Program log: AnchorError occurred. Error Code: AmountAboveMaximum. Error Number: 6001.',
+  'Program BloomBridge1111111111111111111111111111111111111 failed: custom program error: 0x1771'
+];
+
+const mockConnection = (err: unknown, logs: string[]) =>
+  ({
+    simulateTransaction: vi.fn().mockResolvedValue({ value: { err, logs } })
+  }) as unknown as Connection;
+
+const TRANSACTION = {} as VersionedTransaction;
+
+describe('Validation errors', () => {
+  it('should pull the value and limit out of failed logs', () => {
+    const error = validationErrorFromLogs(FAILED_LOCK_LOGS);
+
+    expect(error).toBeInstanceOf(BridgeValidationError);
+    expect(error).toMatchObject({ errorCode: 6001, value: 2_000_000n, limit: 1_000_000n });
+    expect(error?.error).toEqual({ program: 'bloom-bridge', code: 6001, name: 'AmountAboveMaximum' });
+  });
+
+  it('should return undefined when no ValidationFailed event was emitted', () => {
+    expect(validationErrorFromLogs(FAILED_LOCK_LOGS.filter(line => !line.startsWith('Program data:')))).toBeUndefined();
+  });
+
+  it('should surface the enriched error from a failing simulation', async () => {
+    const connection = mockConnection({ InstructionError: [0, { Custom: 6001 }] }, FAILED_LOCK_LOGS);
+
+    await expect(simulateOrThrow(connection, TRANSACTION)).rejects.toMatchObject({
+      name: 'BridgeValidationError',
+      value: 2_000_000n,
+      limit: 1_000_000n
+    });
+  });
+
+  it('should return logs from a successful simulation', async () => {
+    const logs = ['Program BloomBridge1111111111111111111111111111111111111 success'];
+
+    await expect(simulateOrThrow(mockConnection(null, logs), TRANSACTION)).resolves.toEqual(logs);
+  });
+});
+
+describe('Error code registry', () => {
+  it('should give every variant across the programs a distinct code', () => {
+    const owners = new Map<number, string>();
+    for (const program of Object.keys(ERROR_NAMES) as ProgramName[]) {
+      const { start, end } = ERROR_CODE_RANGES[program];
+      expect(start + ERROR_NAMES[program].length - 1).toBeLessThanOrEqual(end);
+      ERROR_NAMES[program].forEach((name, index) => {
+        const code = start + index;
+        expect(owners.get(code)).toBeUndefined();
+        owners.set(code, `${program}.${name}`);
+      });
+    }
+  });
+
+  it('should resolve codes from either program', () => {
+    expect(resolveErrorCode(6500)).toEqual({ program: 'bloom-token', code: 6500, name: 'MintWouldBreakPeg' });
+    expect(resolveErrorCode(6000)).toEqual({ program: 'bloom-bridge', code: 6000, name: 'AmountBelowMinimum' });
+    expect(resolveErrorCode(6499)).toBeUndefined();
+  });
+});
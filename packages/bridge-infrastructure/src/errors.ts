@@ -0,0 +1,204 @@
+import type { Connection, VersionedTransaction } from '@solana/web3.js';
+import { parseEventsFromLogs, type ValidationFailedEvent } from './events';
+
+export type ProgramName = 'bloom-bridge' | 'bloom-token';
+
+/**
+ * Anchor error number range of each program, mirroring their `#[error_code]` offsets
+ */
+export const ERROR_CODE_RANGES: Record<ProgramName, { start: number; end: number }> = {
+  'bloom-bridge': { start: 6000, end: 6499 },
+  'bloom-token': { start: 6500, end: 6999 }
+};
+
+/**
+ * Each program's `ErrorCode` variants in declaration order; keep in sync with the enums
+ */
+export const ERROR_NAMES: Record<ProgramName, readonly string[]> = {
+  'bloom-bridge': [
+    'AmountBelowMinimum',
+    'AmountAboveMaximum',
+    'TransactionAlreadyProcessed',
+    'InvalidMerkleProof',
+    'InsufficientLockedBalance',
+    'UnauthorizedAuthority',
+    'UnauthorizedRelayer',
+    'EscrowInvariantViolated',
+    'InvalidFeeShare',
+    'NoRebateAccrued',
+    'UnexpectedBatchId',
+    'BridgePaused',
+    'InvalidProofDepth',
+    'ProofDepthMismatch',
+    'MathOverflow',
+    'ExcessiveDust',
+    'BootstrapRestricted',
+    'TooManyOutstandingLocks',
+    'InvalidFeeRate',
+    'InsufficientRelayerBond',
+    'ClaimAccountsMismatch',
+    'InvalidProcessedTransaction',
+    'InvalidRecipientAccount',
+    'UnauthorizedBackupPoster',
+    'InvalidBackupRootDelay',
+    'BackupRootNotMature',
+    'InvalidPauseDuration',
+    'PauseTooShort',
+    'BridgeSunsetting',
+    'BridgeMigrated',
+    'BridgeNotSunsetting',
+    'SunsetWindowTooShort',
+    'SunsetWindowOpen',
+    'SunsetWindowClosed',
+    'FeeBuybackDisabled',
+    'NoFeesAccrued',
+    'ReinitializationAttempt',
+    'InvalidVestingSeconds',
+    'InvalidVestingAccount',
+    'VestingNotClaimable',
+    'VestingRequiresSingleUnlock',
+    'UnauthorizedPendingAuthority',
+    'InvalidEmergencyWindow',
+    'EmergencyWindowExpired',
+    'FeeExemptListFull',
+    'UntrustedInstructionInTransaction',
+    'InvalidSlotDuration',
+    'MemoRecordRequired',
+    'BatchTooLarge',
+    'RecipientFrozen',
+    'InvalidProgramData',
+    'SysvarUnavailable',
+    'InvalidRoleChangeCooldown',
+    'RoleChangeCooldown',
+    'InvalidMinLockDuration',
+    'InvalidUserLockedAccount',
+    'MinLockDurationNotMet',
+    'MinLockDurationRequiresSingleUnlock',
+    'WouldUnderfundEscrow',
+    'InsufficientEscrow',
+    'ChainCapExceeded',
+    'ChainCapListFull',
+    'InvalidUnlockVolumeAccount',
+    'UnlockDailyCapExceeded',
+    'UnlockCapRequiresSingleUnlock',
+    'UnsupportedAccountVersion',
+    'AddressSetFull',
+    'AmountBelowSatsMinimum',
+    'InvalidRoot',
+    'RootUnchanged',
+    'RootNotSet',
+    'InvalidEvmAddress',
+    'DefaultEvmAddressNotSet'
+  ],
+  'bloom-token': [
+    'MintWouldBreakPeg',
+    'UnauthorizedMintAuthority',
+    'InvalidAmount',
+    'InsufficientBalance',
+    'InvalidReserveFeed',
+    'ReserveFeedEmpty',
+    'InsufficientReserves',
+    'DuplicateRecipient',
+    'RecipientCountMismatch',
+    'InvalidPeg',
+    'MathOverflow',
+    'BootstrapRestricted',
+    'UnauthorizedCompliance',
+    'FaucetCapExceeded',
+    'ReinitializationAttempt',
+    'RecipientNotWhitelisted',
+    'MetadataTooLong',
+    'GuardPegViolation',
+    'GuardRateLimit',
+    'GuardFrozen',
+    'MintingFrozen',
+    'MissingTransferMemo',
+    'InvalidMemoRecord',
+    'RecipientFrozen',
+    'InvalidProgramData',
+    'UnsupportedAccountVersion',
+    'InvalidBtcUsdRate',
+    'InvalidMaxFeedAge',
+    'ReserveFeedStale',
+    'InvalidReserveTolerance',
+    'ZeroReserves',
+    'ProtectedDestination',
+    'ProtectedDestinationListFull',
+    'InvalidFeedRotationGrace',
+    'InvalidMint',
+    'InvalidRecipientAccount'
+  ]
+};
+
+export interface ProgramErrorInfo {
+  program: ProgramName;
+  code: number;
+  name: string;
+}
+
+/**
+ * Resolve an error number from either program to its variant
+ */
+export function resolveErrorCode(code: number): ProgramErrorInfo | undefined {
+  for (const program of Object.keys(ERROR_CODE_RANGES) as ProgramName[]) {
+    const { start, end } = ERROR_CODE_RANGES[program];
+    const name = ERROR_NAMES[program][code - start];
+    if (code >= start && code <= end && name) {
+      return { program, code, name };
+    }
+  }
+  return undefined;
+}
+
+/**
+ * A program validation error enriched with the value and limit from the
+ * `ValidationFailed` event the program emitted before failing
+ */
+export class BridgeValidationError extends Error {
+  /** The program and variant `errorCode` belongs to, if known */
+  readonly error?: ProgramErrorInfo;
+
+  constructor(
+    readonly errorCode: number,
+    readonly value: bigint,
+    readonly limit: bigint,
+    readonly logs: string[]
+  ) {
+    const error = resolveErrorCode(errorCode);
+    const label = error ? `${error.name} (${error.program})` : `error ${errorCode}`;
+    super(`Validation failed with ${label}: value ${value}, limit ${limit}`);
+    this.name = 'BridgeValidationError';
+    this.error = error;
+  }
+}
+
+/**
+ * Build an enriched error from a failed transaction's logs, using the last
+ * `ValidationFailed` event. Returns undefined when the program emitted none.
+ */
+export function validationErrorFromLogs(logs: string[]): BridgeValidationError | undefined {
+  const failures = parseEventsFromLogs(logs).filter(
+    (event): event is ValidationFailedEvent => event.name === 'ValidationFailed'
+  );
+  const failure = failures[failures.length - 1];
+  if (!failure) {
+    return undefined;
+  }
+  return new BridgeValidationError(failure.errorCode, failure.value, failure.limit, logs);
+}
+
+/**
+ * Simulate a transaction and throw a `BridgeValidationError` if a validation fails.
+ * Returns the simulation logs on success.
+ */
+export async function simulateOrThrow(
+  connection: Connection,
+  transaction: VersionedTransaction
+): Promise<string[]> {
+  const { value } = await connection.simulateTransaction(transaction, { sigVerify: false });
+  const logs = value.logs ?? [];
+  if (value.err) {
+    throw validationErrorFromLogs(logs) ?? new Error(`Simulation failed: ${JSON.stringify(value.err)}`);
+  }
+  return logs;
+}
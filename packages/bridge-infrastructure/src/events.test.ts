@@ -0,0 +1,312 @@
+import { describe, it, expect } from 'vitest';
+import { PublicKey } from '@solana/web3.js';
+import {
+  BorshReader,
+  BurnCategory,
+  decodeEvent,
+  eventDiscriminator,
+  evmDepositHash,
+  parseEventsFromLogs,
+  type TokensLockedEvent,
+  type TokensUnlockedEvent,
+  type UnlockAlreadyProcessedEvent,
+  type MerkleRootUpdatedEvent,
+  type MintEvent,
+  type BurnEvent,
+  type BridgeInitializedEvent,
+  type MintInitializedEvent,
+  type RootSetByAuthorityEvent,
+  type ReserveWithinToleranceEvent,
+  type CollateralRatioEvent
+} from './events';
+
+// Events captured from pre-versioning program builds (no version byte)
+const LEGACY_FIXTURES = {
+  tokensLocked: 'P7jJFMvC+YoHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBzAbDwAAAAAAKgAAADB4MTIzNDU2Nzg5MDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f',
+  tokensUnlocked: 'II/6oj+DU6MHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHByChBwAAAAAAAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh+rq6urq6urq6urq6urq6urq6urq6urq6urq6urq6urqw==',
+  merkleRootUpdated: 'c6IkSB03J4arq6urq6urq6urq6urq6urq6urq6urq6urq6urq6urqwDxU2UAAAAA',
+  mint: 'PwvVhpTCGMsHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHByoAAAAAAAAADQAAAG1pbmluZ19yZXdhcmQ=',
+  burn: 'uA1Bzs6qM1UHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBxEAAAAAAAAACgAAAHJlZGVtcHRpb24='
+};
+
+const V1_TOKENS_LOCKED = 'P7jJFMvC+YoBBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcwGw8AAAAAACoAAAAweDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDEyMzQ1Njc4OTAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHw==';
+
+const V3_TOKENS_LOCKED = 'P7jJFMvC+YoDBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcwGw8AAAAAACoAAAAweDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDEyMzQ1Njc4OTAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHxAnAAAAAAAAAAAAAAAAAAAA4FNWZoQDAAAAAAAAAAAA';
+
+const V4_TOKENS_LOCKED = 'P7jJFMvC+YoEBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcwGw8AAAAAACoAAAAweDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDEyMzQ1Njc4OTAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHxAnAAAAAAAAAAAAAAAAAAAA4FNWZoQDAAAAAAAAAAAAsaQAAAAAAAA=';
+
+const V5_TOKENS_LOCKED = 'P7jJFMvC+YoFBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcwGw8AAAAAACoAAAAweDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDEyMzQ1Njc4OTAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHxAnAAAAAAAAAAAAAAAAAAAA4FNWZoQDAAAAAAAAAAAAsaQAAAAAAAARAAAAAAAAAHh+FzVl/bjPV7r8LqfS6nBhQaTLbuCUI/JxgJmrVajU';
+
+const V6_TOKENS_LOCKED = 'P7jJFMvC+YoGBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcwGw8AAAAAACoAAAAweDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDEyMzQ1Njc4OTAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHxAnAAAAAAAAAAAAAAAAAAAA4FNWZoQDAAAAAAAAAAAAsaQAAAAAAAARAAAAAAAAAHh+FzVl/bjPV7r8LqfS6nBhQaTLbuCUI/JxgJmrVajUWBsAAAAAAAC4CwAAAAAAAA==';
+
+const V7_TOKENS_LOCKED = 'P7jJFMvC+YoHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcwGw8AAAAAACoAAAAweDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDEyMzQ1Njc4OTAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHxAnAAAAAAAAAAAAAAAAAAAA4FNWZoQDAAAAAAAAAAAAsaQAAAAAAAARAAAAAAAAAHh+FzVl/bjPV7r8LqfS6nBhQaTLbuCUI/JxgJmrVajUWBsAAAAAAAC4CwAAAAAAAGQA';
+
+// keccak256(abi.encode(bytes32(0x0707..07), uint256(990000e9), uint256(17), uint256(42161))),
+// computed outside the SDK as a Solidity contract would
+const SOLIDITY_DEPOSIT_HASH = '787e173565fdb8cf57bafc2ea7d2ea706141a4cb6ee09423f2718099ab55a8d4';
+
+const V2_BURN = 'uA1Bzs6qM1UCBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcRAAAAAAAAAAoAAAByZWRlbXB0aW9uAS9CDwAAAAAA';
+
+const V4_MERKLE_ROOT_UPDATED = 'c6IkSB03J4YEq6urq6urq6urq6urq6urq6urq6urq6urq6urq6urq6sA8VNlAAAAAAcAAAAAAAAAFAwAAAA=';
+
+const V1_BRIDGE_INITIALIZED = '+tYgEo2rcisBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQECAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQAQpdToAAAA6AMAAAAAAAAeAA==';
+
+const V1_MINT_INITIALIZED = 'Lqtroa9o7OYBBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQAAAEJsb29tBQAAAEJMT09NCQoAAAAAAAAA';
+
+const V1_ROOT_SET_BY_AUTHORITY = 'Z+rXPGfN0FoBBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQWrq6urq6urq6urq6urq6urq6urq6urq6urq6urq6urqwgAAAAAAAAAFA==';
+
+const V1_UNLOCK_ALREADY_PROCESSED = 'tZODNfSDPnABBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcgoQcAAAAAAAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4fAPFTZQAAAAA=';
+
+const V2_UNLOCK_ALREADY_PROCESSED = 'tZODNfSDPnACAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8A8VNlAAAAAA==';
+
+const V1_RESERVE_WITHIN_TOLERANCE = 'Fmc3u0vlkucBsB31BQAAAAAA4fUFAAAAAAoA';
+// Minted up to 10 BLOOM (9 decimals) against a feed reading of 150_000_000 sats
+const V1_COLLATERAL_RATIO = 'G6f6FSSMJxwBAOQLVAIAAACA0fAIAAAAAJg6AAAAAAAA';
+
+const fromBase64 = (value: string) => new Uint8Array(Buffer.from(value, 'base64'));
+const USER = new PublicKey(new Uint8Array(32).fill(7)).toBase58();
+const TRANSACTION_ID = Uint8Array.from({ length: 32 }, (_, i) => i);
+
+describe('Event parsing', () => {
+  describe('discriminators', () => {
+    it('should match the Anchor event discriminator', () => {
+      expect(Array.from(eventDiscriminator('TokensLocked'))).toEqual(
+        Array.from(fromBase64(LEGACY_FIXTURES.tokensLocked).subarray(0, 8))
+      );
+    });
+  });
+
+  describe('legacy (version 0) events', () => {
+    it('should decode TokensLocked', () => {
+      const event = decodeEvent(fromBase64(LEGACY_FIXTURES.tokensLocked)) as TokensLockedEvent;
+
+      expect(event.name).toBe('TokensLocked');
+      expect(event.version).toBe(0);
+      expect(event.user).toBe(USER);
+      expect(event.amount).toBe(990000n);
+      expect(event.evmAddress).toBe('0x1234567890123456789012345678901234567890');
+      expect(Array.from(event.transactionId)).toEqual(Array.from(TRANSACTION_ID));
+    });
+
+    it('should decode TokensUnlocked', () => {
+      const event = decodeEvent(fromBase64(LEGACY_FIXTURES.tokensUnlocked)) as TokensUnlockedEvent;
+
+      expect(event.version).toBe(0);
+      expect(event.amount).toBe(500000n);
+      expect(event.merkleRoot.every(byte => byte === 0xab)).toBe(true);
+    });
+
+    it('should decode MerkleRootUpdated', () => {
+      const event = decodeEvent(fromBase64(LEGACY_FIXTURES.merkleRootUpdated)) as MerkleRootUpdatedEvent;
+
+      expect(event.version).toBe(0);
+      expect(event.timestamp).toBe(1700000000n);
+    });
+
+    it('should decode Mint and Burn', () => {
+      const mint = decodeEvent(fromBase64(LEGACY_FIXTURES.mint)) as MintEvent;
+      const burn = decodeEvent(fromBase64(LEGACY_FIXTURES.burn)) as BurnEvent;
+
+      expect(mint).toMatchObject({ name: 'Mint', version: 0, to: USER, amount: 42n, reason: 'mining_reward' });
+      expect(burn).toMatchObject({ name: 'Burn', version: 0, from: USER, amount: 17n, reason: 'redemption' });
+    });
+  });
+
+  describe('versioned events', () => {
+    it('should decode a version 1 TokensLocked into the same shape', () => {
+      const legacy = decodeEvent(fromBase64(LEGACY_FIXTURES.tokensLocked)) as TokensLockedEvent;
+      const current = decodeEvent(fromBase64(V1_TOKENS_LOCKED)) as TokensLockedEvent;
+
+      expect(current.version).toBe(1);
+      expect({ ...current, version: 0 }).toEqual(legacy);
+    });
+
+    it('should decode the EVM-scaled amount from version 3 TokensLocked', () => {
+      const event = decodeEvent(fromBase64(V3_TOKENS_LOCKED)) as TokensLockedEvent;
+
+      // 990000 base units at 9 decimals, scaled to 18 EVM decimals
+      expect(event).toMatchObject({ version: 3, amount: 990000n, fee: 10000n, dust: 0n });
+      expect(event.evmAmount).toBe(990000n * 10n ** 9n);
+    });
+
+    it('should decode the destination chain from version 4 TokensLocked', () => {
+      const event = decodeEvent(fromBase64(V4_TOKENS_LOCKED)) as TokensLockedEvent;
+
+      expect(event).toMatchObject({ version: 4, amount: 990000n, chainId: 42161n });
+    });
+
+    it('should decode the nonce and EVM deposit hash from version 5 TokensLocked', () => {
+      const event = decodeEvent(fromBase64(V5_TOKENS_LOCKED)) as TokensLockedEvent;
+
+      expect(event).toMatchObject({ version: 5, chainId: 42161n, lockNonce: 17n });
+      expect(Buffer.from(event.depositHash!).toString('hex')).toBe(SOLIDITY_DEPOSIT_HASH);
+    });
+
+    it('should decode the treasury and relayer fee split from version 6 TokensLocked', () => {
+      const event = decodeEvent(fromBase64(V6_TOKENS_LOCKED)) as TokensLockedEvent;
+
+      expect(event).toMatchObject({ version: 6, fee: 10000n, treasuryFee: 7000n, relayerFee: 3000n });
+      expect(event.treasuryFee! + event.relayerFee!).toBe(event.fee);
+    });
+
+    it('should decode the applied fee rate from version 7 TokensLocked', () => {
+      const event = decodeEvent(fromBase64(V7_TOKENS_LOCKED)) as TokensLockedEvent;
+      // Locked under `fee_rate: 100` toward Arbitrum
+      const feeRate = 100;
+
+      expect(event).toMatchObject({ version: 7, chainId: 42161n, appliedFeeRate: feeRate });
+      // The fee is the gross amount at that rate, rounded up
+      const gross = event.amount + event.fee! + event.dust!;
+      expect(event.fee).toBe((gross * BigInt(feeRate) + 9_999n) / 10_000n);
+    });
+
+    it('should compute the deposit hash the EVM contract expects', () => {
+      const hash = evmDepositHash(USER, 990000n * 10n ** 9n, 17n, 42161n);
+
+      expect(Buffer.from(hash).toString('hex')).toBe(SOLIDITY_DEPOSIT_HASH);
+    });
+
+    it('should decode the burn category and resulting supply from version 2 Burn', () => {
+      const event = decodeEvent(fromBase64(V2_BURN)) as BurnEvent;
+
+      expect(event.version).toBe(2);
+      expect(event.category).toBe(BurnCategory.BRIDGE_RETURN);
+      expect(event.totalSupply).toBe(999983n);
+    });
+
+    it('should decode the attested confirmations from version 4 MerkleRootUpdated', () => {
+      const event = decodeEvent(fromBase64(V4_MERKLE_ROOT_UPDATED)) as MerkleRootUpdatedEvent;
+
+      expect(event).toMatchObject({ version: 4, batchId: 7n, depth: 20, confirmations: 12 });
+    });
+
+    it('should decode the deployment parameters from BridgeInitialized and MintInitialized', () => {
+      const key = (fill: number) => new PublicKey(new Uint8Array(32).fill(fill)).toBase58();
+      const bridge = decodeEvent(fromBase64(V1_BRIDGE_INITIALIZED)) as BridgeInitializedEvent;
+      const mint = decodeEvent(fromBase64(V1_MINT_INITIALIZED)) as MintInitializedEvent;
+
+      expect(bridge).toEqual({
+        name: 'BridgeInitialized',
+        version: 1,
+        bridgeData: key(1),
+        bloomTokenMint: key(2),
+        mintGuard: key(3),
+        relayer: key(4),
+        authority: key(5),
+        maxBridgeAmount: 1_000_000_000_000n,
+        minBridgeAmount: 1_000n,
+        feeRate: 30
+      });
+      expect(mint).toEqual({
+        name: 'MintInitialized',
+        version: 1,
+        mint: key(6),
+        mintData: key(7),
+        mintAuthority: key(5),
+        tokenName: 'Bloom',
+        symbol: 'BLOOM',
+        decimals: 9,
+        btcPerBloom: 10n
+      });
+    });
+
+    it('should decode an authority-set root from RootSetByAuthority', () => {
+      const event = decodeEvent(fromBase64(V1_ROOT_SET_BY_AUTHORITY)) as RootSetByAuthorityEvent;
+
+      expect(event).toMatchObject({
+        name: 'RootSetByAuthority',
+        version: 1,
+        authority: new PublicKey(new Uint8Array(32).fill(5)).toBase58(),
+        batchId: 8n,
+        depth: 20
+      });
+      expect(event.newRoot.every(byte => byte === 0xab)).toBe(true);
+    });
+
+    it('should decode an idempotent resubmit from UnlockAlreadyProcessed', () => {
+      const event = decodeEvent(fromBase64(V1_UNLOCK_ALREADY_PROCESSED)) as UnlockAlreadyProcessedEvent;
+
+      expect(event).toMatchObject({
+        name: 'UnlockAlreadyProcessed',
+        version: 1,
+        user: USER,
+        amount: 500_000n,
+        processedAt: 1_700_000_000n
+      });
+      expect(Array.from(event.transactionId)).toEqual(Array.from(TRANSACTION_ID));
+    });
+
+    it('should decode UnlockAlreadyProcessed v2 without the unverified user and amount', () => {
+      const event = decodeEvent(fromBase64(V2_UNLOCK_ALREADY_PROCESSED)) as UnlockAlreadyProcessedEvent;
+
+      expect(event).toMatchObject({
+        name: 'UnlockAlreadyProcessed',
+        version: 2,
+        processedAt: 1_700_000_000n
+      });
+      expect(event.user).toBeUndefined();
+      expect(event.amount).toBeUndefined();
+      expect(Array.from(event.transactionId)).toEqual(Array.from(TRANSACTION_ID));
+    });
+
+    it('should decode a tolerated reserve shortfall from ReserveWithinTolerance', () => {
+      const event = decodeEvent(fromBase64(V1_RESERVE_WITHIN_TOLERANCE)) as ReserveWithinToleranceEvent;
+
+      expect(event).toEqual({
+        name: 'ReserveWithinTolerance',
+        version: 1,
+        reserveSats: 99_950_000n,
+        requiredSats: 100_000_000n,
+        toleranceBps: 10
+      });
+    });
+
+    it('should report the post-mint ratio against the feed reading in CollateralRatio', () => {
+      const event = decodeEvent(fromBase64(V1_COLLATERAL_RATIO)) as CollateralRatioEvent;
+      const feedReading = 150_000_000n;
+      // 10 BLOOM at 10 BLOOM per BTC needs 1 BTC
+      const requiredSats = 100_000_000n;
+
+      expect(event).toEqual({
+        name: 'CollateralRatio',
+        version: 1,
+        totalSupply: 10_000_000_000n,
+        reservesSats: feedReading,
+        ratioBps: 15_000n
+      });
+      expect(event.ratioBps).toBe((feedReading * 10_000n) / requiredSats);
+    });
+
+    it('should reject truncated event data', () => {
+      const data = fromBase64(V1_TOKENS_LOCKED);
+      expect(() => decodeEvent(data.subarray(0, data.length - 4))).toThrow('Malformed TokensLocked event');
+    });
+
+    it('should ignore unknown events', () => {
+      expect(decodeEvent(new Uint8Array(16))).toBeUndefined();
+    });
+  });
+
+  describe('log parsing', () => {
+    it('should extract events from program logs across versions', () => {
+      const events = parseEventsFromLogs([
+        'Program BloomBridge1111111111111111111111111111111111111 invoke [1]',
+        `Program data: ${LEGACY_FIXTURES.tokensLocked}`,
+        `Program data: ${V1_TOKENS_LOCKED}`,
+        'Program BloomBridge1111111111111111111111111111111111111 success'
+      ]);
+
+      expect(events.map(event => event.version)).toEqual([0, 1]);
+    });
+  });
+
+  describe('BorshReader', () => {
+    it('should read little-endian integers', () => {
+      const reader = new BorshReader(Uint8Array.from([0x01, 0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]));
+
+      expect(reader.u16()).toBe(0x0201);
+      expect(reader.i64()).toBe(-1n);
+      expect(reader.remaining()).toBe(0);
+    });
+  });
+});
@@ -0,0 +1,504 @@
+import { createHash } from 'crypto';
+import { PublicKey } from '@solana/web3.js';
+import { AbiCoder, getBytes, keccak256 } from 'ethers';
+
+/**
+ * On-chain event decoding for the bloom-bridge and bloom-token programs.
+ *
+ * Events emitted before schema versioning (version 0) have no version byte; every
+ * later encoding starts with `version: u8`. All versions decode into the same typed
+ * shape, with fields that older encodings lack left undefined.
+ */
+
+export interface TokensLockedEvent {
+  name: 'TokensLocked';
+  version: number;
+  user: string;
+  amount: bigint;
+  evmAddress: string;
+  transactionId: Uint8Array;
+  fee?: bigint;
+  dust?: bigint;
+  /** `amount` in EVM decimals, as the EVM side should mint it */
+  evmAmount?: bigint;
+  /** Destination chain of the lock */
+  chainId?: bigint;
+  /** Sequence number of the lock across all users */
+  lockNonce?: bigint;
+  /** `evmDepositHash` of the lock, as the EVM contract recomputes it */
+  depositHash?: Uint8Array;
+  /** Part of `fee` kept as protocol fees */
+  treasuryFee?: bigint;
+  /** Part of `fee` routed to the relayer rebate vault */
+  relayerFee?: bigint;
+  /** Fee rate `fee` was charged at, in bps; zero for fee-exempt users */
+  appliedFeeRate?: number;
+}
+
+export interface TokensUnlockedEvent {
+  name: 'TokensUnlocked';
+  version: number;
+  user: string;
+  amount: bigint;
+  transactionId: Uint8Array;
+  merkleRoot: Uint8Array;
+}
+
+/**
+ * An idempotent unlock resubmitted a claim that was already processed; nothing was minted
+ */
+export interface UnlockAlreadyProcessedEvent {
+  name: 'UnlockAlreadyProcessed';
+  version: number;
+  /** Resubmitted recipient, unverified; only version 1 carries it */
+  user?: string;
+  /** Resubmitted amount, unverified; only version 1 carries it */
+  amount?: bigint;
+  transactionId: Uint8Array;
+  processedAt: bigint;
+}
+
+export interface MerkleRootUpdatedEvent {
+  name: 'MerkleRootUpdated';
+  version: number;
+  newRoot: Uint8Array;
+  timestamp: bigint;
+  batchId?: bigint;
+  depth?: number;
+  confirmations?: number;
+}
+
+export interface MintEvent {
+  name: 'Mint';
+  version: number;
+  to: string;
+  amount: bigint;
+  reason: string;
+}
+
+/**
+ * Mirrors bloom-token's `BurnCategory`
+ */
+export enum BurnCategory {
+  REDEMPTION = 0,
+  BRIDGE_RETURN = 1,
+  PENALTY = 2,
+  DEFLATIONARY = 3
+}
+
+export interface BurnEvent {
+  name: 'Burn';
+  version: number;
+  from: string;
+  amount: bigint;
+  reason: string;
+  category?: BurnCategory;
+  totalSupply?: bigint;
+}
+
+/**
+ * Emitted by both programs right before a validation error, carrying the values
+ * the error itself cannot
+ */
+export interface ValidationFailedEvent {
+  name: 'ValidationFailed';
+  version: number;
+  errorCode: number;
+  value: bigint;
+  limit: bigint;
+}
+
+/**
+ * Emitted once by `initialize_bridge`, so indexers can detect a deployment
+ */
+export interface BridgeInitializedEvent {
+  name: 'BridgeInitialized';
+  version: number;
+  bridgeData: string;
+  bloomTokenMint: string;
+  mintGuard: string;
+  relayer: string;
+  authority: string;
+  maxBridgeAmount: bigint;
+  minBridgeAmount: bigint;
+  feeRate: number;
+}
+
+/**
+ * Emitted once by `initialize_bloom_mint`
+ */
+export interface MintInitializedEvent {
+  name: 'MintInitialized';
+  version: number;
+  mint: string;
+  mintData: string;
+  mintAuthority: string;
+  tokenName: string;
+  symbol: string;
+  decimals: number;
+  btcPerBloom: bigint;
+}
+
+/**
+ * A root the authority set in place of the relayer; watchers should verify it
+ */
+export interface RootSetByAuthorityEvent {
+  name: 'RootSetByAuthority';
+  version: number;
+  authority: string;
+  newRoot: Uint8Array;
+  batchId: bigint;
+  depth: number;
+}
+
+/**
+ * A mint allowed with reserves short of the requirement but within `reserve_tolerance_bps`
+ */
+export interface ReserveWithinToleranceEvent {
+  name: 'ReserveWithinTolerance';
+  version: number;
+  reserveSats: bigint;
+  requiredSats: bigint;
+  toleranceBps: number;
+}
+
+/**
+ * Collateralization after a mint, from the reserve feed reading the mint was checked against
+ */
+export interface CollateralRatioEvent {
+  name: 'CollateralRatio';
+  version: number;
+  totalSupply: bigint;
+  reservesSats: bigint;
+  ratioBps: bigint;
+}
+
+export type ProgramEvent =
+  | TokensLockedEvent
+  | TokensUnlockedEvent
+  | UnlockAlreadyProcessedEvent
+  | MerkleRootUpdatedEvent
+  | MintEvent
+  | BurnEvent
+  | ValidationFailedEvent
+  | BridgeInitializedEvent
+  | MintInitializedEvent
+  | RootSetByAuthorityEvent
+  | ReserveWithinToleranceEvent
+  | CollateralRatioEvent;
+
+/**
+ * Minimal little-endian Borsh reader
+ */
+export class BorshReader {
+  private offset = 0;
+
+  constructor(private readonly data: Uint8Array) {}
+
+  remaining(): number {
+    return this.data.length - this.offset;
+  }
+
+  bytes(length: number): Uint8Array {
+    if (length < 0 || this.offset + length > this.data.length) {
+      throw new Error('Unexpected end of event data');
+    }
+    const out = this.data.slice(this.offset, this.offset + length);
+    this.offset += length;
+    return out;
+  }
+
+  u8(): number {
+    return this.bytes(1)[0];
+  }
+
+  bool(): boolean {
+    return this.u8() !== 0;
+  }
+
+  u16(): number {
+    const b = this.bytes(2);
+    return b[0] | (b[1] << 8);
+  }
+
+  u32(): number {
+    const b = this.bytes(4);
+    return (b[0] | (b[1] << 8) | (b[2] << 16)) + b[3] * 0x1000000;
+  }
+
+  u64(): bigint {
+    const b = this.bytes(8);
+    let value = 0n;
+    for (let i = 7; i >= 0; i--) {
+      value = (value << 8n) | BigInt(b[i]);
+    }
+    return value;
+  }
+
+  i64(): bigint {
+    return BigInt.asIntN(64, this.u64());
+  }
+
+  u128(): bigint {
+    const lo = this.u64();
+    const hi = this.u64();
+    return (hi << 64n) | lo;
+  }
+
+  pubkey(): string {
+    return new PublicKey(this.bytes(32)).toBase58();
+  }
+
+  string(): string {
+    const length = this.u32();
+    return new TextDecoder().decode(this.bytes(length));
+  }
+}
+
+function decodeBurnCategory(value: number): BurnCategory {
+  if (!(value in BurnCategory)) {
+    throw new Error(`Unknown burn category ${value}`);
+  }
+  return value as BurnCategory;
+}
+
+type EventDecoder = (reader: BorshReader, version: number) => ProgramEvent;
+
+interface EventSchema {
+  latestVersion: number;
+  decode: EventDecoder;
+}
+
+const EVENT_SCHEMAS: Record<string, EventSchema> = {
+  TokensLocked: {
+    latestVersion: 7,
+    decode: (r, version) => ({
+      name: 'TokensLocked',
+      version,
+      user: r.pubkey(),
+      amount: r.u64(),
+      evmAddress: r.string(),
+      transactionId: r.bytes(32),
+      fee: version >= 2 ? r.u64() : undefined,
+      dust: version >= 2 ? r.u64() : undefined,
+      evmAmount: version >= 3 ? r.u128() : undefined,
+      chainId: version >= 4 ? r.u64() : undefined,
+      lockNonce: version >= 5 ? r.u64() : undefined,
+      depositHash: version >= 5 ? r.bytes(32) : undefined,
+      treasuryFee: version >= 6 ? r.u64() : undefined,
+      relayerFee: version >= 6 ? r.u64() : undefined,
+      appliedFeeRate: version >= 7 ? r.u16() : undefined
+    })
+  },
+  TokensUnlocked: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'TokensUnlocked',
+      version,
+      user: r.pubkey(),
+      amount: r.u64(),
+      transactionId: r.bytes(32),
+      merkleRoot: r.bytes(32)
+    })
+  },
+  UnlockAlreadyProcessed: {
+    latestVersion: 2,
+    decode: (r, version) => ({
+      name: 'UnlockAlreadyProcessed',
+      version,
+      user: version < 2 ? r.pubkey() : undefined,
+      amount: version < 2 ? r.u64() : undefined,
+      transactionId: r.bytes(32),
+      processedAt: r.i64()
+    })
+  },
+  MerkleRootUpdated: {
+    latestVersion: 4,
+    decode: (r, version) => ({
+      name: 'MerkleRootUpdated',
+      version,
+      newRoot: r.bytes(32),
+      timestamp: r.i64(),
+      batchId: version >= 2 ? r.u64() : undefined,
+      depth: version >= 3 ? r.u8() : undefined,
+      confirmations: version >= 4 ? r.u32() : undefined
+    })
+  },
+  Mint: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'Mint',
+      version,
+      to: r.pubkey(),
+      amount: r.u64(),
+      reason: r.string()
+    })
+  },
+  Burn: {
+    latestVersion: 2,
+    decode: (r, version) => ({
+      name: 'Burn',
+      version,
+      from: r.pubkey(),
+      amount: r.u64(),
+      reason: r.string(),
+      category: version >= 2 ? decodeBurnCategory(r.u8()) : undefined,
+      totalSupply: version >= 2 ? r.u64() : undefined
+    })
+  },
+  ValidationFailed: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'ValidationFailed',
+      version,
+      errorCode: r.u32(),
+      value: r.u128(),
+      limit: r.u128()
+    })
+  },
+  BridgeInitialized: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'BridgeInitialized',
+      version,
+      bridgeData: r.pubkey(),
+      bloomTokenMint: r.pubkey(),
+      mintGuard: r.pubkey(),
+      relayer: r.pubkey(),
+      authority: r.pubkey(),
+      maxBridgeAmount: r.u64(),
+      minBridgeAmount: r.u64(),
+      feeRate: r.u16()
+    })
+  },
+  MintInitialized: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'MintInitialized',
+      version,
+      mint: r.pubkey(),
+      mintData: r.pubkey(),
+      mintAuthority: r.pubkey(),
+      tokenName: r.string(),
+      symbol: r.string(),
+      decimals: r.u8(),
+      btcPerBloom: r.u64()
+    })
+  },
+  RootSetByAuthority: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'RootSetByAuthority',
+      version,
+      authority: r.pubkey(),
+      newRoot: r.bytes(32),
+      batchId: r.u64(),
+      depth: r.u8()
+    })
+  },
+  ReserveWithinTolerance: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'ReserveWithinTolerance',
+      version,
+      reserveSats: r.u64(),
+      requiredSats: r.u64(),
+      toleranceBps: r.u16()
+    })
+  },
+  CollateralRatio: {
+    latestVersion: 1,
+    decode: (r, version) => ({
+      name: 'CollateralRatio',
+      version,
+      totalSupply: r.u64(),
+      reservesSats: r.u64(),
+      ratioBps: r.u64()
+    })
+  }
+};
+
+/**
+ * Canonical deposit hash of a Solana-origin lock, matching bloom-bridge's `evm_deposit_hash`
+ *
+ * `keccak256(abi.encode(bytes32 user, uint256 amount, uint256 nonce, uint256 chainId))`
+ * with `user` as the raw Solana pubkey and `amount` in EVM decimals.
+ */
+export function evmDepositHash(user: string, evmAmount: bigint, nonce: bigint, chainId: bigint): Uint8Array {
+  const encoded = AbiCoder.defaultAbiCoder().encode(
+    ['bytes32', 'uint256', 'uint256', 'uint256'],
+    [new PublicKey(user).toBytes(), evmAmount, nonce, chainId]
+  );
+  return getBytes(keccak256(encoded));
+}
+
+/**
+ * Anchor event discriminator: first 8 bytes of sha256("event:<Name>")
+ */
+export function eventDiscriminator(name: string): Uint8Array {
+  return new Uint8Array(createHash('sha256').update(`event:${name}`).digest().subarray(0, 8));
+}
+
+const DISCRIMINATORS = Object.keys(EVENT_SCHEMAS).map(name => ({
+  name,
+  discriminator: eventDiscriminator(name)
+}));
+
+function tryDecode(schema: EventSchema, body: Uint8Array, versioned: boolean): ProgramEvent | undefined {
+  try {
+    const reader = new BorshReader(body);
+    const version = versioned ? reader.u8() : 0;
+    if (versioned && (version < 1 || version > schema.latestVersion)) {
+      return undefined;
+    }
+    const event = schema.decode(reader, version);
+    return reader.remaining() === 0 ? event : undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Decode raw event bytes (discriminator + payload) of any known version.
+ * Returns undefined for events this parser does not track.
+ */
+export function decodeEvent(data: Uint8Array): ProgramEvent | undefined {
+  if (data.length < 8) {
+    return undefined;
+  }
+
+  const match = DISCRIMINATORS.find(({ discriminator }) =>
+    discriminator.every((byte, i) => data[i] === byte)
+  );
+  if (!match) {
+    return undefined;
+  }
+
+  const schema = EVENT_SCHEMAS[match.name];
+  const body = data.subarray(8);
+
+  // Versioned encodings are the common case; fall back to the pre-versioning layout
+  const event = tryDecode(schema, body, true) ?? tryDecode(schema, body, false);
+  if (!event) {
+    throw new Error(`Malformed ${match.name} event`);
+  }
+  return event;
+}
+
+/**
+ * Extract and decode every known event from a transaction's log messages
+ */
+export function parseEventsFromLogs(logs: string[]): ProgramEvent[] {
+  const events: ProgramEvent[] = [];
+  for (const line of logs) {
+    const prefix = 'Program data: ';
+    if (!line.startsWith(prefix)) {
+      continue;
+    }
+    const event = decodeEvent(new Uint8Array(Buffer.from(line.slice(prefix.length), 'base64')));
+    if (event) {
+      events.push(event);
+    }
+  }
+  return events;
+}
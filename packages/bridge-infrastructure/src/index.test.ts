@@ -494,6 +494,26 @@ describe('BridgeUtils', () => {
     });
   });
 
+  describe('Default EVM Address', () => {
+    it('should accept addresses the program stores as a default', () => {
+      expect(BridgeUtils.isValidEvmAddress('0x742d35Cc6634C0532925a3b844Bc454e4438f44e')).toBe(true);
+      expect(BridgeUtils.isValidEvmAddress('0x' + '0'.repeat(40))).toBe(true);
+    });
+
+    it('should reject addresses set_default_evm_address would refuse', () => {
+      expect(BridgeUtils.isValidEvmAddress('')).toBe(false);
+      expect(BridgeUtils.isValidEvmAddress('742d35Cc6634C0532925a3b844Bc454e4438f44e')).toBe(false); // No prefix
+      expect(BridgeUtils.isValidEvmAddress('0x742d35Cc6634C0532925a3b844Bc454e4438f4')).toBe(false); // 39 digits
+      expect(BridgeUtils.isValidEvmAddress('0x742d35Cc6634C0532925a3b844Bc454e4438f44g')).toBe(false); // Not hex
+    });
+
+    it('should check the EIP-55 checksum of mixed-case addresses', () => {
+      expect(BridgeUtils.isValidEvmAddress('0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed')).toBe(true);
+      expect(BridgeUtils.isValidEvmAddress('0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD')).toBe(false); // Last letter mistyped
+      expect(BridgeUtils.isValidEvmAddress('0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed')).toBe(true); // No checksum
+    });
+  });
+
   describe('Transaction Hash Generation', () => {
     it('should generate transaction hash', () => {
       const hash = BridgeUtils.generateTransactionHash(
@@ -6,6 +6,11 @@ import { BloomTokenEVM, MintGuardEVM } from '@mycelia/bloom-contracts';
 import { bloomToSats, satsToBloom } from '@mycelia/tokenomics';
 import CryptoJS from 'crypto-js';
 
+export * from './events';
+export * from './config';
+export * from './errors';
+export * from './compute';
+
 /**
  * Supported bridge chains
  */
@@ -630,6 +635,15 @@ export class BridgeUtils {
     }
   }
 
+  /**
+   * Whether an address is accepted by bloom-bridge's `lock_tokens` and
+   * `set_default_evm_address`: `0x` and 40 hex digits, with a valid EIP-55 checksum
+   * when mixed-case
+   */
+  static isValidEvmAddress(address: string): boolean {
+    return /^0x[0-9a-fA-F]{40}$/.test(address) && ethers.isAddress(address);
+  }
+
   /**
    * Generate bridge transaction hash
    */
@@ -1,6 +1,8 @@
 import { describe, it, expect, beforeEach } from 'vitest';
+import { isFullyReserved } from '@mycelia/tokenomics';
 import {
   StaticReserveFeed,
+  UsdReserveFeed,
   MockSpvProofFeed,
   ComposableReserveFeed,
   composeReserveFeed,
@@ -24,6 +26,28 @@ describe('StaticReserveFeed', () => {
   });
 });
 
+describe('UsdReserveFeed', () => {
+  it('should convert a USD value to sats at the BTC/USD rate', async () => {
+    // $1,000,000.00 at $62,500.00 per BTC, in cents
+    const feed = new UsdReserveFeed(100_000_000n, 6_250_000n);
+    expect(await feed.getLockedBtcSats()).toBe(1_600_000_000n);
+  });
+
+  it('should round down so reserves are never overstated', async () => {
+    expect(await new UsdReserveFeed(1n, 3n).getLockedBtcSats()).toBe(33_333_333n);
+  });
+
+  it('should feed the converted reserves into the peg check', async () => {
+    const sats = await new UsdReserveFeed(100_000_000n, 6_250_000n).getLockedBtcSats();
+    expect(isFullyReserved(sats, 160n)).toBe(true);
+    expect(isFullyReserved(sats, 161n)).toBe(false);
+  });
+
+  it('should reject a zero rate', async () => {
+    await expect(new UsdReserveFeed(100n, 0n).getLockedBtcSats()).rejects.toThrow('BTC/USD rate must be positive');
+  });
+});
+
 describe('MockSpvProofFeed', () => {
   let feed: MockSpvProofFeed;
 
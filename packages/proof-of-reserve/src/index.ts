@@ -1,4 +1,4 @@
-import { ReserveFeed } from '@mycelia/tokenomics';
+import { ReserveFeed, SATS_PER_BTC } from '@mycelia/tokenomics';
 
 export interface SpvUtxoConfig {
   watchAddresses?: string[];
@@ -36,6 +36,25 @@ export class StaticReserveFeed implements ReserveFeed {
   }
 }
 
+/**
+ * Reserve feed for custodians that report a USD value instead of sats
+ * Mirrors bloom-token's `FeedDenomination::Usd` conversion, rounding down so
+ * reserves are never overstated; both values share one USD unit (e.g. cents)
+ */
+export class UsdReserveFeed implements ReserveFeed {
+  constructor(
+    private totalUsd: bigint,
+    private btcUsdRate: bigint
+  ) {}
+
+  async getLockedBtcSats(): Promise<bigint> {
+    if (this.btcUsdRate <= 0n) {
+      throw new Error('BTC/USD rate must be positive');
+    }
+    return (this.totalUsd * SATS_PER_BTC) / this.btcUsdRate;
+  }
+}
+
 /**
  * SPV proof feed interface for future Bitcoin verification
  * Currently stubbed for demo purposes
@@ -9,8 +9,10 @@ import {
   requiredSatsForSupply,
   collateralizationRatio,
   isFullyReserved,
+  isReservedWithinTolerance,
   canMint,
   assertCanMint,
+  assertPegInvariant,
   maxRedeemableBloom,
   quoteRedeemBloomToSats,
   type ReserveFeed,
@@ -94,6 +96,13 @@ describe('Collateralization Math', () => {
     expect(isFullyReserved(200_000_000n, 10n)).toBe(true); // Over-collateralized
     expect(isFullyReserved(50_000_000n, 10n)).toBe(false); // Under-collateralized
   });
+
+  it('should allow a shortfall only within tolerance', () => {
+    // 10 BLOOM needs 100_000_000 sats; 10 bps tolerates down to 99_900_000
+    expect(isReservedWithinTolerance(99_900_000n, 10n, 10)).toBe(true); // Just inside
+    expect(isReservedWithinTolerance(99_899_999n, 10n, 10)).toBe(false); // Just outside
+    expect(isReservedWithinTolerance(99_999_999n, 10n, 0)).toBe(false); // No tolerance
+  });
 });
 
 describe('Mint Guard', () => {
@@ -126,6 +135,42 @@ describe('Mint Guard', () => {
     await expect(assertCanMint(10n, { reserve: mockReserveFeed, supply: mockSupplyFeed }))
       .rejects.toThrow("Mint denied: collateral shortfall. Peg requires locked BTC sats >= BLOOM * 10 ratio.");
   });
+
+  it('should report a feed with zero reserves distinctly', async () => {
+    const emptyReserveFeed: ReserveFeed = {
+      async getLockedBtcSats() {
+        return 0n;
+      }
+    };
+
+    await expect(assertCanMint(1n, { reserve: emptyReserveFeed, supply: mockSupplyFeed }))
+      .rejects.toThrow('Mint denied: reserve feed reports zero reserves.');
+    expect(collateralizationRatio(0n, 5n)).toBe(0);
+    expect(collateralizationRatio(0n, 0n)).toBe(Infinity);
+  });
+});
+
+describe('Peg Invariant Canary', () => {
+  const reserveFeed: ReserveFeed = {
+    async getLockedBtcSats() { return 100_000_000n; } // 1 BTC
+  };
+
+  it('should pass while reserves back the supply', async () => {
+    const supplyFeed: SupplyFeed = {
+      async getBloomOutstanding() { return 10n; }
+    };
+
+    await expect(assertPegInvariant({ reserve: reserveFeed, supply: supplyFeed })).resolves.toBeUndefined();
+  });
+
+  it('should fail on a deliberately under-collateralized state', async () => {
+    const supplyFeed: SupplyFeed = {
+      async getBloomOutstanding() { return 11n; } // Needs 1.1 BTC
+    };
+
+    await expect(assertPegInvariant({ reserve: reserveFeed, supply: supplyFeed }))
+      .rejects.toThrow('Peg invariant violated: 100000000 sats locked, 110000000 required.');
+  });
 });
 
 describe('Redemption Functions', () => {
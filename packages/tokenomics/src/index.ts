@@ -70,6 +70,19 @@ export function isFullyReserved(lockedSats: bigint, outstandingBloom: bigint): b
   return lockedSats >= requiredSatsForSupply(outstandingBloom);
 }
 
+/**
+ * Check if reserves cover supply to within `toleranceBps` of the requirement,
+ * matching bloom-token's `reserve_tolerance_bps` check
+ * @param lockedSats Currently locked BTC satoshis
+ * @param outstandingBloom Current BLOOM supply
+ * @param toleranceBps Shortfall allowed, in basis points of the requirement
+ * @returns true if reserves are within tolerance
+ */
+export function isReservedWithinTolerance(lockedSats: bigint, outstandingBloom: bigint, toleranceBps: number): boolean {
+  const required = requiredSatsForSupply(outstandingBloom);
+  return lockedSats * 10_000n >= required * BigInt(10_000 - toleranceBps);
+}
+
 /**
  * Check if a mint operation would maintain full collateralization
  * @param mintAmountBloom Amount of BLOOM to mint
@@ -91,7 +104,8 @@ export async function canMint(
 
 /**
  * Assert that a mint operation maintains full collateralization
- * Throws if mint would cause under-collateralization
+ * Throws if mint would cause under-collateralization; a feed reporting zero
+ * reserves fails with its own message, like bloom-token's `ZeroReserves`
  * @param mintAmountBloom Amount of BLOOM to mint
  * @param feeds Reserve and supply feeds
  */
@@ -99,10 +113,37 @@ export async function assertCanMint(
   mintAmountBloom: bigint,
   feeds: { reserve: ReserveFeed; supply: SupplyFeed }
 ): Promise<void> {
-  const allowed = await canMint(mintAmountBloom, feeds);
-  if (!allowed) {
+  const [lockedSats, outstandingBloom] = await Promise.all([
+    feeds.reserve.getLockedBtcSats(),
+    feeds.supply.getBloomOutstanding()
+  ]);
+
+  const newSupply = outstandingBloom + mintAmountBloom;
+  if (isFullyReserved(lockedSats, newSupply)) {
+    return;
+  }
+  if (lockedSats === 0n) {
+    throw new Error('Mint denied: reserve feed reports zero reserves.');
+  }
+  throw new Error(
+    "Mint denied: collateral shortfall. Peg requires locked BTC sats >= BLOOM * 10 ratio."
+  );
+}
+
+/**
+ * Assert that current reserves back the outstanding supply, mirroring bloom-token's
+ * `assert_peg_invariant` keeper canary
+ * @param feeds Reserve and supply feeds
+ */
+export async function assertPegInvariant(feeds: { reserve: ReserveFeed; supply: SupplyFeed }): Promise<void> {
+  const [lockedSats, outstandingBloom] = await Promise.all([
+    feeds.reserve.getLockedBtcSats(),
+    feeds.supply.getBloomOutstanding()
+  ]);
+
+  if (!isFullyReserved(lockedSats, outstandingBloom)) {
     throw new Error(
-      "Mint denied: collateral shortfall. Peg requires locked BTC sats >= BLOOM * 10 ratio."
+      `Peg invariant violated: ${lockedSats} sats locked, ${requiredSatsForSupply(outstandingBloom)} required.`
     );
   }
 }